@@ -0,0 +1,61 @@
+use clap::Parser;
+use cocoatly_core::{
+    config::Config,
+    state::GlobalState,
+};
+use cocoatly_installer::doctor::{run_diagnostics, DiagnosticsReport};
+use cocoatly_cli_bridge::output::JsonOutput;
+use tracing_subscriber;
+
+#[derive(Parser)]
+#[command(name = "cocoatly-doctor")]
+#[command(about = "Inspect the environment and report on installed package health")]
+struct Args {
+    #[arg(long)]
+    config: String,
+
+    /// Roll back any dangling pending operations (e.g. an update interrupted
+    /// by a crash) before reporting on package health.
+    #[arg(long)]
+    recover: bool,
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+
+    let result = run(args);
+
+    match result {
+        Ok(report) => {
+            let healthy = report.healthy;
+            JsonOutput::success(report).print();
+            std::process::exit(if healthy { 0 } else { 1 });
+        }
+        Err(e) => {
+            JsonOutput::<()>::failure(e.to_string()).print();
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(args: Args) -> anyhow::Result<DiagnosticsReport> {
+    let config = Config::load_from_file(&args.config)?;
+    let mut state = GlobalState::load_from_file(&config.storage.state_file)?;
+
+    let recovered_packages = if args.recover {
+        state
+            .recover(&config)?
+            .iter()
+            .map(|op| op.name().as_str().to_string())
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let mut report = run_diagnostics(&config, &state)?;
+    report.recovered_packages = recovered_packages;
+
+    Ok(report)
+}
@@ -5,6 +5,7 @@ use cocoatly_core::{
     state::GlobalState,
 };
 use cocoatly_installer::install::{InstallContext, install_package};
+use cocoatly_installer::options::InstallOptions;
 use cocoatly_cli_bridge::output::{JsonOutput, OperationResult};
 use tracing_subscriber;
 
@@ -17,6 +18,23 @@ struct Args {
 
     #[arg(long)]
     artifact_json: String,
+
+    /// Skip if the exact version is already installed instead of erroring.
+    #[arg(long)]
+    needed: bool,
+
+    /// Reinstall over an already-installed version by removing it first.
+    #[arg(long)]
+    force: bool,
+
+    /// Record this install as pulled in by its dependents so it can be
+    /// garbage-collected as an orphan once they're removed.
+    #[arg(long = "as-deps")]
+    as_dependency: bool,
+
+    /// Skip checksum verification for this install.
+    #[arg(long)]
+    skip_verify: bool,
 }
 
 #[tokio::main]
@@ -47,10 +65,17 @@ async fn run(args: Args) -> anyhow::Result<OperationResult> {
 
     let context = InstallContext::new(config, state)?;
 
+    let options = InstallOptions::new()
+        .needed(args.needed)
+        .force(args.force)
+        .as_dependency(args.as_dependency)
+        .skip_verify(args.skip_verify);
+
     let installed = install_package(
         context,
         &artifact,
         vec![],
+        options,
     ).await?;
 
     Ok(OperationResult {
@@ -0,0 +1,96 @@
+use clap::{Parser, Subcommand};
+use cocoatly_core::{
+    config::Config,
+    state::GlobalState,
+    lockfile::{Lockfile, verify_lock},
+};
+use cocoatly_installer::install_from_lockfile;
+use cocoatly_cli_bridge::output::JsonOutput;
+use tracing_subscriber;
+
+#[derive(Parser)]
+#[command(name = "cocoatly-lockfile")]
+#[command(about = "Generate, install from, and verify a cocoatly.lock file")]
+struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Write a lockfile pinning every currently-installed package.
+    Generate {
+        #[arg(long)]
+        config: String,
+    },
+    /// Install exactly what the lockfile pins, skipping registry resolution.
+    Install {
+        #[arg(long)]
+        config: String,
+        #[arg(long, default_value_t = 4)]
+        max_concurrent: usize,
+    },
+    /// Report drift between the lockfile and the current state.
+    Verify {
+        #[arg(long)]
+        config: String,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+
+    let result = run(args).await;
+
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            JsonOutput::<()>::failure(e.to_string()).print();
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run(args: Args) -> anyhow::Result<String> {
+    match args.command {
+        Commands::Generate { config } => {
+            let config = Config::load_from_file(&config)?;
+            let state = GlobalState::load_from_file(&config.storage.state_file)?;
+
+            let lockfile = Lockfile::generate(&state);
+            lockfile.save_to_file(&config.storage.lock_file)?;
+
+            Ok(serde_json::to_string_pretty(&lockfile)?)
+        }
+        Commands::Install { config, max_concurrent } => {
+            let config = Config::load_from_file(&config)?;
+            let state = GlobalState::load_from_file(&config.storage.state_file)?;
+            let lock_file = config.storage.lock_file.clone();
+
+            let installed = install_from_lockfile(&lock_file, config, state, max_concurrent).await?;
+
+            Ok(serde_json::to_string_pretty(&installed)?)
+        }
+        Commands::Verify { config } => {
+            let config = Config::load_from_file(&config)?;
+            let state = GlobalState::load_from_file(&config.storage.state_file)?;
+
+            let lockfile = Lockfile::load_from_file(&config.storage.lock_file)?;
+            let drift = verify_lock(&lockfile, &state);
+            let report = serde_json::to_string_pretty(&drift)?;
+
+            if !drift.is_clean() {
+                println!("{}", report);
+                std::process::exit(1);
+            }
+
+            Ok(report)
+        }
+    }
+}
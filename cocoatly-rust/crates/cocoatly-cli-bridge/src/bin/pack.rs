@@ -0,0 +1,97 @@
+use clap::Parser;
+use cocoatly_core::{
+    manifest::load_manifest,
+    types::{CompressionCodec, HashAlgorithm},
+};
+use cocoatly_installer::{PackOptions, build_package_artifact};
+use cocoatly_cli_bridge::output::JsonOutput;
+use std::path::PathBuf;
+use tracing_subscriber;
+
+#[derive(Parser)]
+#[command(name = "cocoatly-pack")]
+#[command(about = "Pack a source directory into a signed, checksummed package artifact")]
+struct Args {
+    /// Directory whose contents become the archive's contents.
+    #[arg(long)]
+    source: PathBuf,
+
+    /// Package manifest describing what's being packed.
+    #[arg(long)]
+    manifest: PathBuf,
+
+    /// Where to write the packed archive.
+    #[arg(long)]
+    output: PathBuf,
+
+    /// URL the artifact will be published at, recorded in the `PackageArtifact`.
+    #[arg(long)]
+    download_url: String,
+
+    #[arg(long, default_value = "gzip")]
+    codec: String,
+
+    #[arg(long, default_value_t = 9)]
+    level: i32,
+
+    #[arg(long, default_value = "blake3")]
+    checksum_algorithm: String,
+
+    /// Path to a PKCS8-encoded Ed25519 private key to sign the artifact with.
+    #[arg(long)]
+    signing_key: Option<PathBuf>,
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+
+    match run(args) {
+        Ok(artifact) => {
+            JsonOutput::success(artifact).print();
+            std::process::exit(0);
+        }
+        Err(e) => {
+            JsonOutput::<()>::failure(e.to_string()).print();
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(args: Args) -> anyhow::Result<cocoatly_core::types::PackageArtifact> {
+    let manifest = load_manifest(&args.manifest)?;
+
+    let codec = match args.codec.to_lowercase().as_str() {
+        "gzip" => CompressionCodec::Gzip,
+        "zstd" => CompressionCodec::Zstd,
+        other => anyhow::bail!("unknown codec: {} (expected gzip or zstd)", other),
+    };
+
+    let checksum_algorithm = match args.checksum_algorithm.to_lowercase().as_str() {
+        "blake3" => HashAlgorithm::Blake3,
+        "sha256" => HashAlgorithm::Sha256,
+        "sha512" => HashAlgorithm::Sha512,
+        other => anyhow::bail!("unknown checksum algorithm: {}", other),
+    };
+
+    let mut options = PackOptions::new()
+        .codec(codec)
+        .level(args.level)
+        .checksum_algorithm(checksum_algorithm);
+
+    if let Some(signing_key_path) = &args.signing_key {
+        let private_key = std::fs::read(signing_key_path)?;
+        options = options.sign_with(private_key);
+    }
+
+    let artifact = build_package_artifact(
+        &args.source,
+        &manifest,
+        args.download_url,
+        &args.output,
+        &options,
+    )?;
+
+    Ok(artifact)
+}
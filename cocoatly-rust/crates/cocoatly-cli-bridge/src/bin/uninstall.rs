@@ -19,12 +19,13 @@ struct Args {
     package: String,
 }
 
-fn main() {
+#[tokio::main]
+async fn main() {
     tracing_subscriber::fmt::init();
 
     let args = Args::parse();
 
-    let result = run(args);
+    let result = run(args).await;
 
     match result {
         Ok(op_result) => {
@@ -38,7 +39,7 @@ fn main() {
     }
 }
 
-fn run(args: Args) -> anyhow::Result<OperationResult> {
+async fn run(args: Args) -> anyhow::Result<OperationResult> {
     let config = Config::load_from_file(&args.config)?;
     let state = GlobalState::load_from_file(&config.storage.state_file)?;
 
@@ -49,7 +50,7 @@ fn run(args: Args) -> anyhow::Result<OperationResult> {
         .map(|p| p.version.to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
-    uninstall_package(config, state, &package_name)?;
+    uninstall_package(config, state, &package_name).await?;
 
     Ok(OperationResult {
         operation: "uninstall".to_string(),
@@ -0,0 +1,257 @@
+use cocoatly_core::error::{CocoatlyError, Result};
+use cocoatly_core::types::CompressionCodec;
+use flate2::Compression as GzCompression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{File, create_dir_all};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder};
+use walkdir::WalkDir;
+
+/// First bytes of a gzip stream (RFC 1952) and a zstd frame (RFC 8478),
+/// used to tell the two archive formats apart without trusting whatever
+/// codec the package metadata claims was used.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Behavior every tar-based archive codec must implement: pack a directory
+/// into a single archive file, unpack an archive back into a directory, and
+/// list an archive's contents without fully extracting it. `level` ranges
+/// over each codec's own scale (flate2's 0-9, zstd's 1-22); callers pick a
+/// codec via [`codec_for`] rather than implementing this directly.
+pub trait ArchiveCodec {
+    fn compress(&self, source_dir: &Path, output_file: &Path, level: i32) -> Result<u64>;
+    fn decompress(&self, archive_path: &Path, output_dir: &Path) -> Result<Vec<PathBuf>>;
+    fn list_contents(&self, archive_path: &Path) -> Result<Vec<String>>;
+}
+
+fn tar_builder_for_dir(source_dir: &Path, tar: &mut Builder<impl Write>) -> Result<()> {
+    for entry in WalkDir::new(source_dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_file() {
+            let relative_path = path.strip_prefix(source_dir)
+                .map_err(|e| CocoatlyError::IoError(
+                    io::Error::new(io::ErrorKind::Other, e.to_string())
+                ))?;
+
+            tar.append_path_with_name(path, relative_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves `entry_path` against `output_dir`, treating a leading `/` as
+/// re-rooted under `output_dir` (rather than trusted as absolute) and
+/// refusing any `..` that would pop back out of it, so a crafted archive
+/// member can't write outside the extraction directory.
+fn safe_extract_path(output_dir: &Path, entry_path: &Path) -> Result<PathBuf> {
+    let mut resolved = output_dir.to_path_buf();
+
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => {
+                if !resolved.pop() || !resolved.starts_with(output_dir) {
+                    return Err(CocoatlyError::VerificationFailed(format!(
+                        "archive member escapes extraction directory: {}",
+                        entry_path.display()
+                    )));
+                }
+            }
+        }
+    }
+
+    if !resolved.starts_with(output_dir) {
+        return Err(CocoatlyError::VerificationFailed(format!(
+            "archive member escapes extraction directory: {}",
+            entry_path.display()
+        )));
+    }
+
+    Ok(resolved)
+}
+
+/// Same escape check as [`safe_extract_path`], applied to a symlink or
+/// hardlink's target rather than the member's own path: the target is
+/// resolved relative to the member's containing directory, since that's
+/// where the filesystem would resolve it at access time.
+///
+/// Unlike `safe_extract_path`, an absolute link target (`Component::RootDir`
+/// or `Component::Prefix`) is rejected outright rather than re-rooted under
+/// `output_dir`: `entry.unpack` (via `std::os::unix::fs::symlink`/a raw
+/// hardlink) writes the link with the archive's literal, unmodified target,
+/// so "re-root it for our own bookkeeping and check that" would validate a
+/// path this function never actually makes the link point at — the
+/// filesystem still resolves `/tmp/victim` as `/tmp/victim`, fully escaping
+/// `output_dir`. A link target must be a pure relative path for this
+/// resolve-and-check approach to mean anything.
+fn validate_link_target(output_dir: &Path, member_path: &Path, link_target: &Path) -> Result<()> {
+    let member_dir = member_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut resolved = output_dir.join(member_dir);
+
+    for component in link_target.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(CocoatlyError::VerificationFailed(format!(
+                    "archive member {} has an absolute link target: {}",
+                    member_path.display(),
+                    link_target.display()
+                )));
+            }
+            Component::ParentDir => {
+                if !resolved.pop() || !resolved.starts_with(output_dir) {
+                    return Err(CocoatlyError::VerificationFailed(format!(
+                        "archive member {} has a link target that escapes the extraction directory",
+                        member_path.display()
+                    )));
+                }
+            }
+        }
+    }
+
+    if !resolved.starts_with(output_dir) {
+        return Err(CocoatlyError::VerificationFailed(format!(
+            "archive member {} has a link target that escapes the extraction directory",
+            member_path.display()
+        )));
+    }
+
+    Ok(())
+}
+
+fn unpack_tar(archive: &mut Archive<impl Read>, output_dir: &Path) -> Result<Vec<PathBuf>> {
+    create_dir_all(output_dir)?;
+
+    let mut extracted_files = Vec::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_path_buf();
+        let output_file_path = safe_extract_path(output_dir, &entry_path)?;
+
+        if let Some(link_target) = entry.link_name()? {
+            validate_link_target(output_dir, &entry_path, &link_target)?;
+        }
+
+        if let Some(parent) = output_file_path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        entry.unpack(&output_file_path)?;
+        extracted_files.push(output_file_path);
+    }
+
+    Ok(extracted_files)
+}
+
+fn list_tar(archive: &mut Archive<impl Read>) -> Result<Vec<String>> {
+    let mut contents = Vec::new();
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let path = entry.path()?;
+        contents.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(contents)
+}
+
+pub struct GzipCodec;
+
+impl ArchiveCodec for GzipCodec {
+    fn compress(&self, source_dir: &Path, output_file: &Path, level: i32) -> Result<u64> {
+        let tar_gz = File::create(output_file)?;
+        let level = (level.clamp(0, 9)) as u32;
+        let enc = GzEncoder::new(tar_gz, GzCompression::new(level));
+        let mut tar = Builder::new(enc);
+
+        tar_builder_for_dir(source_dir, &mut tar)?;
+
+        let enc = tar.into_inner()?;
+        let tar_gz = enc.finish()?;
+        let file_size = tar_gz.metadata()?.len();
+
+        Ok(file_size)
+    }
+
+    fn decompress(&self, archive_path: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        let tar_gz = File::open(archive_path)?;
+        let dec = GzDecoder::new(tar_gz);
+        let mut archive = Archive::new(dec);
+
+        unpack_tar(&mut archive, output_dir)
+    }
+
+    fn list_contents(&self, archive_path: &Path) -> Result<Vec<String>> {
+        let tar_gz = File::open(archive_path)?;
+        let dec = GzDecoder::new(tar_gz);
+        let mut archive = Archive::new(dec);
+
+        list_tar(&mut archive)
+    }
+}
+
+pub struct ZstdCodec;
+
+impl ArchiveCodec for ZstdCodec {
+    fn compress(&self, source_dir: &Path, output_file: &Path, level: i32) -> Result<u64> {
+        let tar_zst = File::create(output_file)?;
+        let level = level.clamp(1, 22);
+        let enc = zstd::Encoder::new(tar_zst, level)?.auto_finish();
+        let mut tar = Builder::new(enc);
+
+        tar_builder_for_dir(source_dir, &mut tar)?;
+
+        drop(tar);
+
+        let file_size = std::fs::metadata(output_file)?.len();
+
+        Ok(file_size)
+    }
+
+    fn decompress(&self, archive_path: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        let tar_zst = File::open(archive_path)?;
+        let dec = zstd::Decoder::new(tar_zst)?;
+        let mut archive = Archive::new(dec);
+
+        unpack_tar(&mut archive, output_dir)
+    }
+
+    fn list_contents(&self, archive_path: &Path) -> Result<Vec<String>> {
+        let tar_zst = File::open(archive_path)?;
+        let dec = zstd::Decoder::new(tar_zst)?;
+        let mut archive = Archive::new(dec);
+
+        list_tar(&mut archive)
+    }
+}
+
+/// Returns the codec implementation for `codec`.
+pub fn codec_for(codec: &CompressionCodec) -> Box<dyn ArchiveCodec> {
+    match codec {
+        CompressionCodec::Gzip => Box::new(GzipCodec),
+        CompressionCodec::Zstd => Box::new(ZstdCodec),
+    }
+}
+
+/// Sniffs `archive_path`'s first bytes to tell which codec it was packed
+/// with, independent of any codec recorded in package metadata.
+pub fn detect_codec(archive_path: &Path) -> Result<CompressionCodec> {
+    let mut header = [0u8; 4];
+    let mut file = File::open(archive_path)?;
+    let bytes_read = file.read(&mut header)?;
+
+    if bytes_read >= 4 && header == ZSTD_MAGIC {
+        Ok(CompressionCodec::Zstd)
+    } else if bytes_read >= 2 && header[..2] == GZIP_MAGIC {
+        Ok(CompressionCodec::Gzip)
+    } else {
+        Err(CocoatlyError::InstallationFailed(
+            format!("Unrecognized archive format for {}", archive_path.display())
+        ))
+    }
+}
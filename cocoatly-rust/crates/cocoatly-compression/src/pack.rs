@@ -0,0 +1,127 @@
+use cocoatly_core::error::{CocoatlyError, Result};
+use cocoatly_core::types::CompressionCodec;
+use flate2::Compression as GzCompression;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use tar::{Builder, Header};
+use walkdir::WalkDir;
+
+/// Path components a packed archive must never contain: they'd either
+/// escape the install directory on extract (`..`, an absolute path) or
+/// collide with cocoatly's own content-addressed store layout under
+/// `install_root/.store`.
+fn validate_relative_path(relative: &Path) -> Result<()> {
+    if relative.is_absolute() {
+        return Err(CocoatlyError::InstallationFailed(
+            format!("refusing to pack absolute path: {}", relative.display())
+        ));
+    }
+
+    for component in relative.components() {
+        match component {
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(CocoatlyError::InstallationFailed(
+                    format!("refusing to pack unsafe path: {}", relative.display())
+                ));
+            }
+            Component::Normal(part) if part == ".store" => {
+                return Err(CocoatlyError::InstallationFailed(
+                    format!("refusing to pack reserved path: {}", relative.display())
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn append_deterministic(
+    tar: &mut Builder<impl Write>,
+    source: &Path,
+    relative: &Path,
+) -> Result<()> {
+    let mut file = File::open(source)?;
+    let size = file.metadata()?.len();
+
+    // Fixed mtime/mode/ownership so two builds of identical file contents
+    // produce byte-identical archives regardless of who built them or when.
+    let mut header = Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_cksum();
+
+    tar.append_data(&mut header, relative, &mut file)?;
+    Ok(())
+}
+
+fn collect_sorted_entries(source_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+
+    for entry in WalkDir::new(source_dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let relative = path.strip_prefix(source_dir).map_err(|e| {
+            CocoatlyError::InstallationFailed(format!("file outside source dir: {}", e))
+        })?;
+
+        validate_relative_path(relative)?;
+        entries.push(relative.to_path_buf());
+    }
+
+    // Sorted so archive member order never depends on filesystem iteration
+    // order, the other half (besides fixed headers) of reproducible output.
+    entries.sort();
+
+    Ok(entries)
+}
+
+/// Packs `source_dir` into `output_file` with `codec` at `level`,
+/// normalizing every file's tar header to a fixed mtime/mode/ownership and
+/// writing members in sorted path order so identical source trees always
+/// produce a byte-identical archive. Returns the archive's size and the
+/// sorted list of relative paths it contains.
+pub fn build_archive_deterministic(
+    source_dir: &Path,
+    output_file: &Path,
+    codec: &CompressionCodec,
+    level: i32,
+) -> Result<(u64, Vec<PathBuf>)> {
+    let entries = collect_sorted_entries(source_dir)?;
+
+    let file = File::create(output_file)?;
+
+    match codec {
+        CompressionCodec::Gzip => {
+            let level = (level.clamp(0, 9)) as u32;
+            let encoder = GzEncoder::new(file, GzCompression::new(level));
+            let mut tar = Builder::new(encoder);
+            for relative in &entries {
+                append_deterministic(&mut tar, &source_dir.join(relative), relative)?;
+            }
+            let mut encoder = tar.into_inner()?;
+            encoder.flush()?;
+        }
+        CompressionCodec::Zstd => {
+            let level = level.clamp(1, 22);
+            let encoder = zstd::Encoder::new(file, level)?.auto_finish();
+            let mut tar = Builder::new(encoder);
+            for relative in &entries {
+                append_deterministic(&mut tar, &source_dir.join(relative), relative)?;
+            }
+            drop(tar);
+        }
+    }
+
+    let size = std::fs::metadata(output_file)?.len();
+
+    Ok((size, entries))
+}
@@ -69,6 +69,15 @@ pub struct HooksConfig {
     pub post_install: Vec<String>,
     pub pre_uninstall: Vec<String>,
     pub post_uninstall: Vec<String>,
+    pub post_update: Vec<String>,
+    /// If true, a failing post-install/post-uninstall/post-update hook aborts
+    /// the operation. Pre-hooks always abort on failure regardless of this flag.
+    pub fail_on_post_hook_error: bool,
+    /// Master switch for running any configured hook at all. Operators flip
+    /// this off to neutralize hooks fleet-wide (e.g. while auditing a
+    /// registry compromise); `InstallOptions::skip_hooks` offers the same
+    /// override for a single untrusted install.
+    pub enabled: bool,
 }
 
 impl Config {
@@ -140,6 +149,9 @@ impl Config {
                 post_install: vec![],
                 pre_uninstall: vec![],
                 post_uninstall: vec![],
+                post_update: vec![],
+                fail_on_post_hook_error: false,
+                enabled: true,
             },
         }
     }
@@ -17,6 +17,7 @@ pub enum CocoatlyError {
     ConfigError(String),
     StateError(String),
     RegistryError(String),
+    HookExecutionFailed(String),
 }
 
 impl fmt::Display for CocoatlyError {
@@ -39,6 +40,7 @@ impl fmt::Display for CocoatlyError {
             CocoatlyError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
             CocoatlyError::StateError(msg) => write!(f, "State error: {}", msg),
             CocoatlyError::RegistryError(msg) => write!(f, "Registry error: {}", msg),
+            CocoatlyError::HookExecutionFailed(msg) => write!(f, "Hook execution failed: {}", msg),
         }
     }
 }
@@ -4,6 +4,7 @@ pub mod config;
 pub mod manifest;
 pub mod state;
 pub mod registry;
+pub mod lockfile;
 
 pub use error::{CocoatlyError, Result};
 pub use types::*;
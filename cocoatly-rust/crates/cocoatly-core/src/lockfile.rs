@@ -0,0 +1,137 @@
+use crate::types::{PackageName, Version, HashAlgorithm};
+use crate::error::Result;
+use crate::state::GlobalState;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// One fully-resolved entry in a `cocoatly.lock`: the exact version that was
+/// selected, where it was fetched from, and the content hash of the artifact
+/// that was downloaded for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: PackageName,
+    pub version: Version,
+    pub url: String,
+    pub hash_algorithm: HashAlgorithm,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub lockfile_version: String,
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    pub fn new(packages: Vec<LockedPackage>) -> Self {
+        Self {
+            lockfile_version: "1".to_string(),
+            packages,
+        }
+    }
+
+    pub fn find(&self, name: &PackageName) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| &p.name == name)
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let lockfile: Lockfile = serde_json::from_str(&content)?;
+        Ok(lockfile)
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Builds a lockfile pinning every currently-installed package to its
+    /// exact version, download URL, and checksum, sorted by `PackageName` so
+    /// regenerating the lockfile from unchanged state produces byte-identical
+    /// output.
+    pub fn generate(state: &GlobalState) -> Self {
+        let mut packages: Vec<LockedPackage> = state
+            .list_packages()
+            .into_iter()
+            .map(|pkg| LockedPackage {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+                url: pkg.download_url.clone(),
+                hash_algorithm: pkg.checksum_algorithm.clone(),
+                hash: pkg.checksum.clone(),
+            })
+            .collect();
+
+        packages.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+
+        Self::new(packages)
+    }
+}
+
+/// Reports how far a `GlobalState` has drifted from what a lockfile pins:
+/// packages the lockfile expects that aren't installed, packages installed
+/// that the lockfile doesn't know about, and packages present in both but
+/// installed at a different version or with a mismatched checksum.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockDrift {
+    pub missing: Vec<PackageName>,
+    pub extra: Vec<PackageName>,
+    pub version_mismatch: Vec<(PackageName, Version, Version)>,
+    pub checksum_mismatch: Vec<PackageName>,
+}
+
+impl LockDrift {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.version_mismatch.is_empty()
+            && self.checksum_mismatch.is_empty()
+    }
+}
+
+/// Compares `state` against `lockfile` and reports any drift, cargo-style:
+/// every locked package should be installed at exactly the pinned version
+/// and checksum, and no unlocked package should be present.
+pub fn verify_lock(lockfile: &Lockfile, state: &GlobalState) -> LockDrift {
+    let mut drift = LockDrift::default();
+
+    for locked in &lockfile.packages {
+        match state.get_package(&locked.name) {
+            None => drift.missing.push(locked.name.clone()),
+            Some(installed) if installed.version != locked.version => {
+                drift.version_mismatch.push((
+                    locked.name.clone(),
+                    locked.version.clone(),
+                    installed.version.clone(),
+                ));
+            }
+            Some(installed) if installed.checksum != locked.hash
+                || installed.checksum_algorithm != locked.hash_algorithm => {
+                drift.checksum_mismatch.push(locked.name.clone());
+            }
+            Some(_) => {}
+        }
+    }
+
+    for installed in state.list_packages() {
+        if lockfile.find(&installed.name).is_none() {
+            drift.extra.push(installed.name.clone());
+        }
+    }
+
+    drift
+}
+
+/// Builds a lockfile from the resolved package set, ready to be written to `cocoatly.lock`.
+pub fn generate_lockfile(packages: Vec<LockedPackage>) -> Lockfile {
+    Lockfile::new(packages)
+}
+
+pub fn load_lockfile<P: AsRef<Path>>(path: P) -> Result<Lockfile> {
+    Lockfile::load_from_file(path)
+}
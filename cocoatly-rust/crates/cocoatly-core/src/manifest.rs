@@ -27,9 +27,43 @@ struct PackageSection {
     categories: Option<Vec<String>>,
 }
 
+/// The manifest serialization formats `load_manifest`/`save_manifest` can read and write,
+/// selected by the file extension (`.json`, `.toml`, `.yaml`/`.yml`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ManifestFormat {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(ManifestFormat::Json),
+            Some("toml") => Ok(ManifestFormat::Toml),
+            Some("yaml") | Some("yml") => Ok(ManifestFormat::Yaml),
+            other => Err(CocoatlyError::InvalidManifest(
+                format!("Unsupported manifest extension: {:?}", other)
+            )),
+        }
+    }
+}
+
 pub fn load_manifest<P: AsRef<Path>>(path: P) -> Result<PackageManifest> {
+    let format = ManifestFormat::from_path(path.as_ref())?;
     let content = std::fs::read_to_string(path)?;
-    let manifest_file: ManifestFile = serde_json::from_str(&content)?;
+    load_manifest_from_str(&content, format)
+}
+
+pub fn load_manifest_from_str(content: &str, format: ManifestFormat) -> Result<PackageManifest> {
+    let manifest_file: ManifestFile = match format {
+        ManifestFormat::Json => serde_json::from_str(content)?,
+        ManifestFormat::Toml => toml::from_str(content)
+            .map_err(|e| CocoatlyError::InvalidManifest(format!("Invalid TOML manifest: {}", e)))?,
+        ManifestFormat::Yaml => serde_yaml::from_str(content)
+            .map_err(|e| CocoatlyError::InvalidManifest(format!("Invalid YAML manifest: {}", e)))?,
+    };
 
     let version = Version::parse(&manifest_file.package.version)
         .ok_or_else(|| CocoatlyError::InvalidManifest(
@@ -90,10 +124,28 @@ fn parse_version_requirement(req: &str) -> Result<crate::types::VersionRequireme
 
     let req = req.trim();
 
+    if req.contains(',') {
+        let constraints = req
+            .split(',')
+            .map(|part| parse_single_constraint(part.trim()))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(VersionRequirement::And(constraints));
+    }
+
+    parse_single_constraint(req)
+}
+
+fn parse_single_constraint(req: &str) -> Result<crate::types::VersionRequirement> {
+    use crate::types::VersionRequirement;
+
     if req == "*" || req.is_empty() {
         return Ok(VersionRequirement::Any);
     }
 
+    if let Some(version_str) = req.strip_prefix("~") {
+        return parse_tilde_requirement(version_str);
+    }
+
     if let Some(version_str) = req.strip_prefix("^") {
         let version = Version::parse(version_str)
             .ok_or_else(|| CocoatlyError::InvalidManifest(
@@ -103,37 +155,29 @@ fn parse_version_requirement(req: &str) -> Result<crate::types::VersionRequireme
     }
 
     if let Some(version_str) = req.strip_prefix(">=") {
-        let version = Version::parse(version_str.trim())
-            .ok_or_else(|| CocoatlyError::InvalidManifest(
-                format!("Invalid version requirement: {}", req)
-            ))?;
+        let version = parse_comparison_version(version_str.trim(), req)?;
         return Ok(VersionRequirement::GreaterThanOrEqual(version));
     }
 
     if let Some(version_str) = req.strip_prefix(">") {
-        let version = Version::parse(version_str.trim())
-            .ok_or_else(|| CocoatlyError::InvalidManifest(
-                format!("Invalid version requirement: {}", req)
-            ))?;
+        let version = parse_comparison_version(version_str.trim(), req)?;
         return Ok(VersionRequirement::GreaterThan(version));
     }
 
     if let Some(version_str) = req.strip_prefix("<=") {
-        let version = Version::parse(version_str.trim())
-            .ok_or_else(|| CocoatlyError::InvalidManifest(
-                format!("Invalid version requirement: {}", req)
-            ))?;
+        let version = parse_comparison_version(version_str.trim(), req)?;
         return Ok(VersionRequirement::LessThanOrEqual(version));
     }
 
     if let Some(version_str) = req.strip_prefix("<") {
-        let version = Version::parse(version_str.trim())
-            .ok_or_else(|| CocoatlyError::InvalidManifest(
-                format!("Invalid version requirement: {}", req)
-            ))?;
+        let version = parse_comparison_version(version_str.trim(), req)?;
         return Ok(VersionRequirement::LessThan(version));
     }
 
+    if is_wildcard_requirement(req) {
+        return parse_wildcard_requirement(req);
+    }
+
     let version = Version::parse(req)
         .ok_or_else(|| CocoatlyError::InvalidManifest(
             format!("Invalid version requirement: {}", req)
@@ -141,6 +185,93 @@ fn parse_version_requirement(req: &str) -> Result<crate::types::VersionRequireme
     Ok(VersionRequirement::Exact(version))
 }
 
+/// Parses the version operand of a `>=`/`>`/`<=`/`<` constraint, tolerating
+/// partial versions (e.g. "1.2" in ">=1.2, <2.0") the same way tilde and
+/// wildcard requirements do, defaulting missing components to 0.
+fn parse_comparison_version(version_str: &str, req: &str) -> Result<Version> {
+    let (major, minor, patch) = parse_partial_version(version_str).ok_or_else(|| {
+        CocoatlyError::InvalidManifest(format!("Invalid version requirement: {}", req))
+    })?;
+    Ok(Version::new(major, minor.unwrap_or(0), patch.unwrap_or(0)))
+}
+
+/// Parses a dot-separated version prefix (e.g. "1", "1.2", "1.2.3"), stopping
+/// at the first missing component. Used by tilde requirements and
+/// comparison operands, which are allowed to omit trailing components.
+fn parse_partial_version(s: &str) -> Option<(u32, Option<u32>, Option<u32>)> {
+    let parts: Vec<&str> = s.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let major = parts[0].parse().ok()?;
+    let minor = parts.get(1).map(|p| p.parse()).transpose().ok()?;
+    let patch = parts.get(2).map(|p| p.parse()).transpose().ok()?;
+
+    Some((major, minor, patch))
+}
+
+fn parse_tilde_requirement(version_str: &str) -> Result<crate::types::VersionRequirement> {
+    use crate::types::VersionRequirement;
+
+    let invalid = || CocoatlyError::InvalidManifest(
+        format!("Invalid version requirement: ~{}", version_str)
+    );
+
+    let (major, minor, patch) = parse_partial_version(version_str).ok_or_else(invalid)?;
+
+    let min = Version::new(major, minor.unwrap_or(0), patch.unwrap_or(0));
+
+    let max = if minor.is_some() {
+        Version::new(major, minor.unwrap() + 1, 0)
+    } else {
+        Version::new(major + 1, 0, 0)
+    };
+
+    Ok(VersionRequirement::Range { min, max })
+}
+
+fn is_wildcard_requirement(req: &str) -> bool {
+    req.split('.').any(|part| part == "x" || part == "X" || part == "*")
+}
+
+fn parse_wildcard_requirement(req: &str) -> Result<crate::types::VersionRequirement> {
+    use crate::types::VersionRequirement;
+
+    let invalid = || CocoatlyError::InvalidManifest(
+        format!("Invalid version requirement: {}", req)
+    );
+
+    let parts: Vec<&str> = req.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(invalid());
+    }
+
+    let major: u32 = parts[0].parse().map_err(|_| invalid())?;
+    let is_wild = |p: &str| p == "x" || p == "X" || p == "*";
+
+    match parts.get(1) {
+        None => Ok(VersionRequirement::Range {
+            min: Version::new(major, 0, 0),
+            max: Version::new(major + 1, 0, 0),
+        }),
+        Some(m) if is_wild(m) => Ok(VersionRequirement::Range {
+            min: Version::new(major, 0, 0),
+            max: Version::new(major + 1, 0, 0),
+        }),
+        Some(m) => {
+            let minor: u32 = m.parse().map_err(|_| invalid())?;
+            match parts.get(2) {
+                Some(p) if is_wild(p) => Ok(VersionRequirement::Range {
+                    min: Version::new(major, minor, 0),
+                    max: Version::new(major, minor + 1, 0),
+                }),
+                _ => Err(invalid()),
+            }
+        }
+    }
+}
+
 pub fn save_manifest<P: AsRef<Path>>(manifest: &PackageManifest, path: P) -> Result<()> {
     let package_section = PackageSection {
         name: manifest.package.name.0.clone(),
@@ -163,7 +294,15 @@ pub fn save_manifest<P: AsRef<Path>>(manifest: &PackageManifest, path: P) -> Res
         features: Some(manifest.features.clone()),
     };
 
-    let content = serde_json::to_string_pretty(&manifest_file)?;
+    let format = ManifestFormat::from_path(path.as_ref())?;
+    let content = match format {
+        ManifestFormat::Json => serde_json::to_string_pretty(&manifest_file)?,
+        ManifestFormat::Toml => toml::to_string_pretty(&manifest_file)
+            .map_err(|e| CocoatlyError::InvalidManifest(format!("Failed to serialize TOML manifest: {}", e)))?,
+        ManifestFormat::Yaml => serde_yaml::to_string(&manifest_file)
+            .map_err(|e| CocoatlyError::InvalidManifest(format!("Failed to serialize YAML manifest: {}", e)))?,
+    };
+
     std::fs::write(path, content)?;
     Ok(())
 }
@@ -188,5 +327,10 @@ fn version_req_to_string(req: &crate::types::VersionRequirement) -> String {
         VersionRequirement::Range { min, max } => {
             format!(">={},<{}", min.to_string(), max.to_string())
         }
+        VersionRequirement::And(constraints) => constraints
+            .iter()
+            .map(version_req_to_string)
+            .collect::<Vec<_>>()
+            .join(","),
     }
 }
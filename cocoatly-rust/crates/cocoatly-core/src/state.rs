@@ -1,5 +1,6 @@
 use crate::types::{InstalledPackage, PackageName, Version};
 use crate::error::{CocoatlyError, Result};
+use crate::config::Config;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::collections::HashMap;
@@ -9,11 +10,42 @@ use chrono::{DateTime, Utc};
 pub struct GlobalState {
     pub version: String,
     pub last_updated: DateTime<Utc>,
+    /// At most one `InstalledPackage` per name: installing a different
+    /// version of an already-installed package replaces this entry rather
+    /// than adding a second one, so only one version of a package can be
+    /// installed at a time. Installers must clean up the replaced version's
+    /// install directory and store references themselves, only once the new
+    /// version has installed successfully (see
+    /// `cocoatly_installer::install::cleanup_superseded_install`).
     pub installed_packages: HashMap<PackageName, InstalledPackage>,
     pub pending_operations: Vec<String>,
     pub metadata: StateMetadata,
 }
 
+/// A journal entry recorded in `GlobalState::pending_operations` before an
+/// operation starts mutating the filesystem, so a crash mid-operation can be
+/// detected and rolled back on the next load instead of leaving the package
+/// half-installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PendingOp {
+    Update {
+        name: PackageName,
+        from_version: Version,
+        to_version: Version,
+        backup_path: String,
+        install_path: String,
+        old_package: InstalledPackage,
+    },
+}
+
+impl PendingOp {
+    pub fn name(&self) -> &PackageName {
+        match self {
+            PendingOp::Update { name, .. } => name,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateMetadata {
     pub total_packages: usize,
@@ -45,6 +77,14 @@ impl GlobalState {
 
         let content = std::fs::read_to_string(path)?;
         let state: GlobalState = serde_json::from_str(&content)?;
+
+        if !state.pending_operations.is_empty() {
+            tracing::warn!(
+                "State has {} dangling pending operation(s) from an interrupted run; call recover() before mutating installs",
+                state.pending_operations.len()
+            );
+        }
+
         Ok(state)
     }
 
@@ -88,17 +128,102 @@ impl GlobalState {
         self.installed_packages.values().collect()
     }
 
+    pub fn record_pending_op(&mut self, op: &PendingOp) -> Result<()> {
+        self.pending_operations.push(serde_json::to_string(op)?);
+        Ok(())
+    }
+
+    pub fn clear_pending_op(&mut self, name: &PackageName) {
+        self.pending_operations.retain(|entry| {
+            match serde_json::from_str::<PendingOp>(entry) {
+                Ok(op) => op.name() != name,
+                Err(_) => true,
+            }
+        });
+    }
+
+    /// Parses every entry in `pending_operations`, skipping any that don't
+    /// deserialize (e.g. written by a future journal format).
+    pub fn dangling_pending_ops(&self) -> Vec<PendingOp> {
+        self.pending_operations
+            .iter()
+            .filter_map(|entry| serde_json::from_str(entry).ok())
+            .collect()
+    }
+
+    /// Rolls back every dangling pending operation left behind by a crash:
+    /// for an `Update`, the pre-update install directory (moved aside into
+    /// `backup_path` before the old version was removed) is restored to
+    /// `install_path` and the old `InstalledPackage` record is reinstated —
+    /// unless the update it's rolling back had already succeeded. `update()`
+    /// commits the new version to `state` (via the install it runs) before
+    /// it clears the pending op and removes the backup a few lines later, so
+    /// a crash in that narrow window leaves a dangling `PendingOp::Update`
+    /// whose `to_version` is already what's installed. Blindly restoring the
+    /// backup in that case would silently revert a successful update and
+    /// destroy the new version's install; instead this only clears the
+    /// stale pending op and its now-redundant backup.
+    pub fn recover(&mut self, config: &Config) -> Result<Vec<PendingOp>> {
+        let ops = self.dangling_pending_ops();
+
+        for op in &ops {
+            match op {
+                PendingOp::Update { name, to_version, backup_path, install_path, old_package, .. } => {
+                    let backup = Path::new(backup_path);
+
+                    let already_succeeded = self
+                        .get_package(name)
+                        .map(|current| &current.version == to_version)
+                        .unwrap_or(false);
+
+                    if already_succeeded {
+                        tracing::info!(
+                            "Update of {} to {} already completed; clearing stale pending op",
+                            name.as_str(),
+                            to_version.to_string()
+                        );
+                        if backup.exists() {
+                            std::fs::remove_dir_all(backup)?;
+                        }
+                    } else {
+                        tracing::warn!("Recovering dangling update for package {}", name.as_str());
+
+                        let install = Path::new(install_path);
+
+                        if backup.exists() {
+                            if install.exists() {
+                                std::fs::remove_dir_all(install)?;
+                            }
+                            std::fs::rename(backup, install)?;
+                            self.add_package(old_package.clone());
+                        }
+                    }
+
+                    self.clear_pending_op(name);
+                }
+            }
+        }
+
+        self.save_to_file(&config.storage.state_file)?;
+
+        Ok(ops)
+    }
+
+    /// Recomputes `total_packages` and `total_size_bytes`. Size is counted
+    /// once per distinct store object hash across all installed packages,
+    /// since files sharing a hash are hard-linked to the same on-disk bytes.
     pub fn update_metadata(&mut self) {
         self.metadata.total_packages = self.installed_packages.len();
+
+        let mut seen_hashes = std::collections::HashSet::new();
         self.metadata.total_size_bytes = self.installed_packages
             .values()
-            .map(|pkg| {
-                pkg.files.iter()
-                    .filter_map(|path| std::fs::metadata(path).ok())
-                    .map(|m| m.len())
-                    .sum::<u64>()
-            })
+            .flat_map(|pkg| pkg.files.iter())
+            .filter(|file| seen_hashes.insert(file.hash.clone()))
+            .filter_map(|file| std::fs::metadata(&file.path).ok())
+            .map(|m| m.len())
             .sum();
+
         self.last_updated = Utc::now();
     }
 }
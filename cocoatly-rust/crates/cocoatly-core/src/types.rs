@@ -16,7 +16,7 @@ impl PackageName {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Version {
     pub major: u32,
     pub minor: u32,
@@ -36,9 +36,20 @@ impl Version {
         }
     }
 
+    /// Parses `major.minor.patch[-prerelease][+build]`, e.g. `1.2.3-rc.1+meta`.
     pub fn parse(s: &str) -> Option<Self> {
-        let parts: Vec<&str> = s.split('.').collect();
-        if parts.len() < 3 {
+        let (core_and_pre, build) = match s.split_once('+') {
+            Some((core_and_pre, build)) => (core_and_pre, Some(build.to_string())),
+            None => (s, None),
+        };
+
+        let (core, prerelease) = match core_and_pre.split_once('-') {
+            Some((core, prerelease)) => (core, Some(prerelease.to_string())),
+            None => (core_and_pre, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() != 3 {
             return None;
         }
 
@@ -46,11 +57,103 @@ impl Version {
         let minor = parts[1].parse().ok()?;
         let patch = parts[2].parse().ok()?;
 
-        Some(Self::new(major, minor, patch))
+        Some(Self {
+            major,
+            minor,
+            patch,
+            prerelease,
+            build,
+        })
     }
 
     pub fn to_string(&self) -> String {
-        format!("{}.{}.{}", self.major, self.minor, self.patch)
+        let mut s = format!("{}.{}.{}", self.major, self.minor, self.patch);
+        if let Some(prerelease) = &self.prerelease {
+            s.push('-');
+            s.push_str(prerelease);
+        }
+        if let Some(build) = &self.build {
+            s.push('+');
+            s.push_str(build);
+        }
+        s
+    }
+
+    fn same_major_minor_patch(&self, other: &Version) -> bool {
+        self.major == other.major && self.minor == other.minor && self.patch == other.patch
+    }
+
+    /// The first version that a `Compatible` (caret) requirement on `self`
+    /// excludes: bumps the leftmost nonzero of major/minor, or the patch if
+    /// both are zero, matching Cargo's caret-requirement semantics.
+    fn next_incompatible(&self) -> Version {
+        if self.major > 0 {
+            Version::new(self.major + 1, 0, 0)
+        } else if self.minor > 0 {
+            Version::new(0, self.minor + 1, 0)
+        } else {
+            Version::new(0, 0, self.patch + 1)
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Semver precedence: major/minor/patch compare numerically, then a version
+/// *with* a prerelease ranks below the same version without one, and two
+/// prereleases compare identifier-by-identifier (split on `.`); build
+/// metadata never affects ordering.
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.patch.cmp(&other.patch))
+            .then_with(|| compare_prerelease(self.prerelease.as_deref(), other.prerelease.as_deref()))
+    }
+}
+
+fn compare_prerelease(a: Option<&str>, b: Option<&str>) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let mut a_ids = a.split('.');
+            let mut b_ids = b.split('.');
+
+            loop {
+                return match (a_ids.next(), b_ids.next()) {
+                    (None, None) => Ordering::Equal,
+                    (None, Some(_)) => Ordering::Less,
+                    (Some(_), None) => Ordering::Greater,
+                    (Some(a_id), Some(b_id)) => {
+                        let ord = compare_prerelease_identifier(a_id, b_id);
+                        if ord == Ordering::Equal {
+                            continue;
+                        }
+                        ord
+                    }
+                };
+            }
+        }
+    }
+}
+
+/// Numeric identifiers compare numerically and always rank below
+/// alphanumeric ones (per semver); otherwise compare lexically.
+fn compare_prerelease_identifier(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+        (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+        (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
     }
 }
 
@@ -88,6 +191,50 @@ pub enum VersionRequirement {
     LessThanOrEqual(Version),
     Compatible(Version),
     Any,
+    And(Vec<VersionRequirement>),
+}
+
+impl VersionRequirement {
+    /// Evaluates whether `version` satisfies this requirement. A prerelease
+    /// version only ever satisfies a requirement whose own bound names that
+    /// exact major.minor.patch (matching Cargo's convention of hiding
+    /// prereleases from requirements that didn't ask for that version
+    /// specifically), even when it would otherwise fall within range.
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            VersionRequirement::Exact(v) => version == v,
+            VersionRequirement::Range { min, max } => {
+                let prerelease_allowed = version.prerelease.is_none()
+                    || version.same_major_minor_patch(min)
+                    || version.same_major_minor_patch(max);
+
+                prerelease_allowed && version >= min && version < max
+            }
+            VersionRequirement::GreaterThan(v) => {
+                let prerelease_allowed = version.prerelease.is_none() || version.same_major_minor_patch(v);
+                prerelease_allowed && version > v
+            }
+            VersionRequirement::GreaterThanOrEqual(v) => {
+                let prerelease_allowed = version.prerelease.is_none() || version.same_major_minor_patch(v);
+                prerelease_allowed && version >= v
+            }
+            VersionRequirement::LessThan(v) => {
+                let prerelease_allowed = version.prerelease.is_none() || version.same_major_minor_patch(v);
+                prerelease_allowed && version < v
+            }
+            VersionRequirement::LessThanOrEqual(v) => {
+                let prerelease_allowed = version.prerelease.is_none() || version.same_major_minor_patch(v);
+                prerelease_allowed && version <= v
+            }
+            VersionRequirement::Compatible(v) => {
+                let prerelease_allowed = version.prerelease.is_none() || version.same_major_minor_patch(v);
+                let upper = v.next_incompatible();
+                prerelease_allowed && version >= v && version < &upper
+            }
+            VersionRequirement::Any => true,
+            VersionRequirement::And(reqs) => reqs.iter().all(|r| r.matches(version)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +247,20 @@ pub struct PackageManifest {
     pub features: HashMap<String, Vec<String>>,
 }
 
+/// One file installed from a package: its path on disk, the digest of the
+/// content-addressed store object (under `install_root/.store`) it's linked
+/// from, and the size/algorithm that digest was computed with. Captured at
+/// install time so `verify_installation` can later re-hash the file in place
+/// and detect tampering or bit rot, not just a missing file — the same
+/// filename -> size + digest table a Debian Release file keeps per entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFile {
+    pub path: String,
+    pub hash: String,
+    pub size: u64,
+    pub algorithm: HashAlgorithm,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledPackage {
     pub id: Uuid,
@@ -108,8 +269,10 @@ pub struct InstalledPackage {
     pub install_path: String,
     pub installed_at: DateTime<Utc>,
     pub requested_by: Vec<PackageName>,
-    pub files: Vec<String>,
+    pub files: Vec<StoredFile>,
     pub checksum: String,
+    pub checksum_algorithm: HashAlgorithm,
+    pub download_url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,9 +285,38 @@ pub struct PackageArtifact {
     pub checksum_algorithm: HashAlgorithm,
     pub signature: Option<String>,
     pub size: u64,
+    pub compression_codec: CompressionCodec,
+    pub compression_level: i32,
+    /// A `ClearSignedManifest` envelope (relative path -> per-file digest,
+    /// clear-signed as one block) covering every file the archive unpacks
+    /// to, so a single signature transitively authenticates each extracted
+    /// file rather than only the archive blob's own `checksum`. `None` for
+    /// artifacts packed before this existed, or packed without a signing key.
+    #[serde(default)]
+    pub signed_manifest: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Archive codec an artifact was (or, when building one, should be) packed
+/// with. Decompression always auto-detects the real codec from the
+/// archive's magic bytes regardless of this field, so artifacts built
+/// before a new codec existed keep extracting correctly; this field only
+/// drives which codec a new archive is built with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionCodec {
+    Gzip,
+    Zstd,
+}
+
+impl CompressionCodec {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CompressionCodec::Gzip => "gzip",
+            CompressionCodec::Zstd => "zstd",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HashAlgorithm {
     Blake3,
     Sha256,
@@ -0,0 +1,83 @@
+use cocoatly_core::error::{CocoatlyError, Result};
+use cocoatly_core::types::HashAlgorithm;
+use crate::hash::HashComputer;
+use crate::signature::verify_signature;
+
+/// One trusted public key, identified by a short id derived from its own
+/// digest (the way a GPG key id is derived from the key's fingerprint) so
+/// verification failures can name which keys were in the keyring without
+/// printing raw key material.
+pub struct TrustedKey {
+    pub key_id: String,
+    pub public_key: Vec<u8>,
+}
+
+/// A set of trusted public keys, loaded from `SecurityConfig::trusted_keys`.
+/// An artifact (or signed manifest) is accepted if its signature validates
+/// against *any* key in the keyring, mirroring how a GPG keyring accepts a
+/// signature from any key it holds.
+pub struct Keyring {
+    keys: Vec<TrustedKey>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self { keys: vec![] }
+    }
+
+    /// Loads every hex-encoded public key in `trusted_keys`, skipping
+    /// entries that aren't valid hex rather than failing the whole keyring.
+    pub fn from_hex_keys(trusted_keys: &[String]) -> Self {
+        let keys = trusted_keys
+            .iter()
+            .filter_map(|hex_key| {
+                let public_key = hex::decode(hex_key.trim()).ok()?;
+                Some(TrustedKey {
+                    key_id: key_id_for(&public_key),
+                    public_key,
+                })
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    pub fn add(&mut self, public_key: Vec<u8>) -> String {
+        let key_id = key_id_for(&public_key);
+        self.keys.push(TrustedKey { key_id: key_id.clone(), public_key });
+        key_id
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn key_ids(&self) -> Vec<&str> {
+        self.keys.iter().map(|k| k.key_id.as_str()).collect()
+    }
+
+    /// Verifies `signature` over `message` against every key in the
+    /// keyring and returns the id of whichever key matched first. Errors
+    /// with `InvalidSignature` naming how many keys were tried if none did.
+    pub fn verify_any(&self, message: &[u8], signature: &[u8]) -> Result<String> {
+        for key in &self.keys {
+            if verify_signature(&key.public_key, message, signature).is_ok() {
+                return Ok(key.key_id.clone());
+            }
+        }
+
+        Err(CocoatlyError::InvalidSignature(
+            format!("signature did not match any of {} trusted key(s) in the keyring", self.keys.len())
+        ))
+    }
+}
+
+impl Default for Keyring {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn key_id_for(public_key: &[u8]) -> String {
+    HashComputer::compute(public_key, &HashAlgorithm::Blake3)[..16].to_string()
+}
@@ -1,7 +1,11 @@
 pub mod hash;
 pub mod signature;
 pub mod verify;
+pub mod keyring;
+pub mod manifest_signature;
 
-pub use hash::{HashComputer, compute_file_hash};
+pub use hash::{HashComputer, compute_file_hash, verify_file_hash};
 pub use signature::{SignatureVerifier, sign_data, verify_signature};
-pub use verify::{verify_package_integrity, verify_artifact};
+pub use verify::{verify_package_integrity, verify_artifact, verify_directory_against_checksum_table};
+pub use keyring::{Keyring, TrustedKey};
+pub use manifest_signature::ClearSignedManifest;
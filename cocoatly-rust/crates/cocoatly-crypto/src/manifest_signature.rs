@@ -0,0 +1,78 @@
+use cocoatly_core::error::{CocoatlyError, Result};
+use crate::keyring::Keyring;
+use std::collections::HashMap;
+
+const BEGIN_MANIFEST: &str = "-----BEGIN COCOATLY SIGNED MANIFEST-----";
+const BEGIN_SIGNATURE: &str = "-----BEGIN COCOATLY SIGNATURE-----";
+const END_MANIFEST: &str = "-----END COCOATLY SIGNED MANIFEST-----";
+
+/// A manifest checksum table carried alongside its own detached signature,
+/// the way Debian's InRelease file carries a `Release` file's per-package
+/// checksum table and its OpenPGP signature inline: the signed bytes are
+/// the manifest text verbatim, so one signature transitively authenticates
+/// every checksum the table lists.
+pub struct ClearSignedManifest {
+    pub manifest_text: String,
+    signature: Vec<u8>,
+}
+
+impl ClearSignedManifest {
+    /// Parses the envelope produced by [`ClearSignedManifest::encode`].
+    pub fn parse(content: &str) -> Result<Self> {
+        let body = content.strip_prefix(BEGIN_MANIFEST).ok_or_else(|| {
+            CocoatlyError::InvalidManifest("missing signed manifest header".to_string())
+        })?;
+
+        let (manifest_text, rest) = body.split_once(BEGIN_SIGNATURE).ok_or_else(|| {
+            CocoatlyError::InvalidManifest("missing signature section".to_string())
+        })?;
+
+        let (signature_hex, _) = rest.split_once(END_MANIFEST).ok_or_else(|| {
+            CocoatlyError::InvalidManifest("missing signed manifest footer".to_string())
+        })?;
+
+        let signature = hex::decode(signature_hex.trim()).map_err(|_| {
+            CocoatlyError::InvalidManifest("malformed signature encoding".to_string())
+        })?;
+
+        Ok(Self {
+            manifest_text: manifest_text.trim().to_string(),
+            signature,
+        })
+    }
+
+    /// Builds the envelope: manifest text, then its detached signature.
+    pub fn encode(manifest_text: &str, signature: &[u8]) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n",
+            BEGIN_MANIFEST,
+            manifest_text.trim(),
+            BEGIN_SIGNATURE,
+            hex::encode(signature),
+            END_MANIFEST,
+        )
+    }
+
+    /// Verifies the signature over the manifest text against `keyring` and,
+    /// only once it validates against a trusted key, returns the
+    /// path -> digest checksum table the manifest lists along with the id
+    /// of the key that authenticated it.
+    pub fn verify(&self, keyring: &Keyring) -> Result<(HashMap<String, String>, String)> {
+        let key_id = keyring.verify_any(self.manifest_text.as_bytes(), &self.signature)?;
+        Ok((parse_checksum_table(&self.manifest_text), key_id))
+    }
+}
+
+/// Parses `<hex digest> <relative path>` lines, the same shape as a Debian
+/// Release file's per-file checksum table.
+fn parse_checksum_table(manifest_text: &str) -> HashMap<String, String> {
+    manifest_text
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let path = parts.next()?;
+            Some((path.to_string(), hash.to_string()))
+        })
+        .collect()
+}
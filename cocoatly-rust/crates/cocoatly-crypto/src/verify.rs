@@ -1,8 +1,9 @@
 use cocoatly_core::types::{PackageArtifact, HashAlgorithm};
 use cocoatly_core::error::{CocoatlyError, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use crate::hash::{verify_file_hash, compute_file_hash};
-use crate::signature::verify_signature;
+use crate::keyring::Keyring;
+use std::collections::HashMap;
 
 pub fn verify_package_integrity<P: AsRef<Path>>(
     path: P,
@@ -12,27 +13,113 @@ pub fn verify_package_integrity<P: AsRef<Path>>(
     verify_file_hash(path, expected_checksum, algorithm)
 }
 
+/// Verifies `artifact`'s checksum and, if it carries a signature, checks it
+/// against every key in `keyring` (accepting the first match, GPG-keyring
+/// style). Returns the id of the key that matched, or `None` if the
+/// artifact carries no signature or `keyring` holds no keys — signature
+/// verification is opportunistic, not mandatory, matching how
+/// `checksum`/`signature` are independent fields on `PackageArtifact`.
+///
+/// This only covers the archive blob's own `checksum`/`signature` — it never
+/// looks at `artifact.signed_manifest`, so it does not by itself authenticate
+/// the files the archive unpacks to. A caller that also wants that per-file
+/// guarantee must separately parse and verify `signed_manifest` (see
+/// `ClearSignedManifest::verify`) and check its checksum table against the
+/// extracted directory with `verify_directory_against_checksum_table`; an
+/// absent or stripped `artifact.signature` does not exempt it from that
+/// second check.
 pub fn verify_artifact<P: AsRef<Path>>(
     artifact_path: P,
     artifact: &PackageArtifact,
-    public_key: Option<&[u8]>,
-) -> Result<()> {
+    keyring: &Keyring,
+) -> Result<Option<String>> {
     verify_file_hash(
         artifact_path.as_ref(),
         &artifact.checksum,
         &artifact.checksum_algorithm,
     )?;
 
-    if let (Some(signature_hex), Some(pub_key)) = (&artifact.signature, public_key) {
-        let signature = hex::decode(signature_hex)
-            .map_err(|_| CocoatlyError::InvalidSignature(
-                "Invalid signature format".to_string()
-            ))?;
+    let Some(signature_hex) = &artifact.signature else {
+        return Ok(None);
+    };
+
+    if keyring.is_empty() {
+        return Ok(None);
+    }
+
+    let signature = hex::decode(signature_hex)
+        .map_err(|_| CocoatlyError::InvalidSignature(
+            "Invalid signature format".to_string()
+        ))?;
+
+    let file_hash = compute_file_hash(artifact_path, &artifact.checksum_algorithm)?;
+    let message = file_hash.as_bytes();
+
+    let key_id = keyring.verify_any(message, &signature).map_err(|_| {
+        CocoatlyError::InvalidSignature(format!(
+            "no trusted key verified the signature for artifact {} {}",
+            artifact.name.as_str(),
+            artifact.version.to_string(),
+        ))
+    })?;
 
-        let file_hash = compute_file_hash(artifact_path, &artifact.checksum_algorithm)?;
-        let message = file_hash.as_bytes();
+    Ok(Some(key_id))
+}
+
+/// Verifies every entry of a checksum table (as produced by a
+/// `ClearSignedManifest`) against the files actually present under
+/// `directory`, naming the offending file on the first mismatch. Also fails
+/// if `directory` contains any file that isn't a key in `table`: otherwise an
+/// attacker could smuggle in an extra, unsigned file alongside a directory
+/// whose every *named* entry still checks out, defeating the claim that the
+/// manifest's single signature authenticates the directory's entire content.
+pub fn verify_directory_against_checksum_table<P: AsRef<Path>>(
+    directory: P,
+    table: &HashMap<String, String>,
+    algorithm: &HashAlgorithm,
+) -> Result<()> {
+    let dir_path = directory.as_ref();
 
-        verify_signature(pub_key, message, &signature)?;
+    for (relative_path, expected_hash) in table {
+        let file_path = dir_path.join(relative_path);
+        verify_file_hash(&file_path, expected_hash, algorithm).map_err(|_| {
+            CocoatlyError::InvalidSignature(format!(
+                "checksum mismatch for {} against the signed manifest",
+                relative_path,
+            ))
+        })?;
+    }
+
+    let mut actual_files = Vec::new();
+    list_relative_files(dir_path, Path::new(""), &mut actual_files)?;
+
+    for relative_path in &actual_files {
+        let key = relative_path.to_string_lossy().replace('\\', "/");
+        if !table.contains_key(key.as_str()) {
+            return Err(CocoatlyError::InvalidSignature(format!(
+                "{} is present in the directory but not in the signed manifest",
+                key,
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Recursively collects every file under `directory`, as paths relative to
+/// it, so `verify_directory_against_checksum_table` can detect files that
+/// were smuggled in alongside the manifest's named entries.
+fn list_relative_files(directory: &Path, prefix: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = prefix.join(entry.file_name());
+
+        if path.is_dir() {
+            list_relative_files(&path, &relative, out)?;
+        } else {
+            out.push(relative);
+        }
     }
 
     Ok(())
@@ -1,12 +1,27 @@
 use cocoatly_core::error::{CocoatlyError, Result};
 use cocoatly_core::config::NetworkConfig;
-use reqwest::{Client, Response};
+use cocoatly_core::types::HashAlgorithm;
+use cocoatly_crypto::hash::compute_file_hash;
+use reqwest::{Client, StatusCode};
 use std::path::{Path, PathBuf};
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
-use futures::stream::StreamExt;
+use futures::stream::{self, StreamExt};
+
+pub mod registry_client;
+pub use registry_client::RegistryClient;
+
+/// The hash a downloaded artifact is expected to match, e.g. the value pinned
+/// for it in `cocoatly.lock`.
+#[derive(Debug, Clone)]
+pub struct ExpectedHash {
+    pub algorithm: HashAlgorithm,
+    pub hash: String,
+}
 
 pub struct Downloader {
     client: Client,
@@ -42,7 +57,20 @@ impl Downloader {
         &self,
         url: &str,
         destination: P,
-        progress_callback: Option<Box<dyn Fn(u64, u64) + Send>>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    ) -> Result<DownloadResult> {
+        self.download_verified(url, destination, progress_callback, None).await
+    }
+
+    /// Like `download`, but hashes the written file and fails with
+    /// `CocoatlyError::HashMismatch` if it doesn't match `expected_hash` (e.g. a
+    /// value pinned in `cocoatly.lock`).
+    pub async fn download_verified<P: AsRef<Path>>(
+        &self,
+        url: &str,
+        destination: P,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        expected_hash: Option<&ExpectedHash>,
     ) -> Result<DownloadResult> {
         let mut attempts = 0;
         let max_attempts = self.config.retry_attempts;
@@ -50,7 +78,7 @@ impl Downloader {
         loop {
             attempts += 1;
 
-            match self.download_internal(url, destination.as_ref(), progress_callback.as_ref()).await {
+            match self.download_internal(url, destination.as_ref(), progress_callback.as_ref(), expected_hash).await {
                 Ok(result) => return Ok(result),
                 Err(e) if attempts < max_attempts => {
                     sleep(Duration::from_millis(self.config.retry_delay_ms)).await;
@@ -65,10 +93,21 @@ impl Downloader {
         &self,
         url: &str,
         destination: &Path,
-        progress_callback: Option<&Box<dyn Fn(u64, u64) + Send>>,
+        progress_callback: Option<&Box<dyn Fn(u64, u64) + Send + Sync>>,
+        expected_hash: Option<&ExpectedHash>,
     ) -> Result<DownloadResult> {
-        let response = self.client
-            .get(url)
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let existing_len = std::fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if existing_len > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing_len));
+        }
+
+        let response = request
             .send()
             .await
             .map_err(|e| CocoatlyError::DownloadFailed(
@@ -81,14 +120,17 @@ impl Downloader {
             ));
         }
 
-        let total_size = response.content_length().unwrap_or(0);
-        let mut downloaded = 0u64;
+        let resuming = existing_len > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
 
-        if let Some(parent) = destination.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+        let mut downloaded = if resuming { existing_len } else { 0 };
+        let total_size = downloaded + response.content_length().unwrap_or(0);
+
+        let mut file = if resuming {
+            OpenOptions::new().append(true).open(destination)?
+        } else {
+            File::create(destination)?
+        };
 
-        let mut file = File::create(destination)?;
         let mut stream = response.bytes_stream();
 
         while let Some(chunk) = stream.next().await {
@@ -106,6 +148,16 @@ impl Downloader {
 
         file.flush()?;
 
+        if let Some(expected) = expected_hash {
+            let actual = compute_file_hash(destination, &expected.algorithm)?;
+            if actual != expected.hash {
+                return Err(CocoatlyError::HashMismatch {
+                    expected: expected.hash.clone(),
+                    actual,
+                });
+            }
+        }
+
         Ok(DownloadResult {
             url: url.to_string(),
             destination: destination.to_path_buf(),
@@ -114,27 +166,40 @@ impl Downloader {
         })
     }
 
+    /// Runs `downloads` with exactly `max_concurrent` in flight at any moment
+    /// (instead of stalling each chunk on its slowest member), reporting
+    /// aggregate progress across the whole batch through `progress_callback`.
     pub async fn download_multiple(
         &self,
         downloads: Vec<DownloadTask>,
+        progress_callback: Option<Arc<dyn Fn(u64, u64) + Send + Sync>>,
     ) -> Result<Vec<DownloadResult>> {
         let max_concurrent = self.config.max_concurrent_downloads;
-        let mut results = Vec::new();
-
-        for chunk in downloads.chunks(max_concurrent) {
-            let futures: Vec<_> = chunk
-                .iter()
-                .map(|task| self.download(&task.url, &task.destination, None))
-                .collect();
-
-            let chunk_results = futures::future::join_all(futures).await;
-
-            for result in chunk_results {
-                results.push(result?);
-            }
-        }
+        let total_tasks = downloads.len() as u64;
+        let completed = Arc::new(AtomicU64::new(0));
+
+        let results: Vec<Result<DownloadResult>> = stream::iter(downloads)
+            .map(|task| {
+                let completed = Arc::clone(&completed);
+                let progress_callback = progress_callback.clone();
+                async move {
+                    let result = self
+                        .download_verified(&task.url, &task.destination, None, task.expected_hash.as_ref())
+                        .await;
+
+                    let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                    if let Some(callback) = &progress_callback {
+                        callback(done, total_tasks);
+                    }
+
+                    result
+                }
+            })
+            .buffer_unordered(max_concurrent)
+            .collect()
+            .await;
 
-        Ok(results)
+        results.into_iter().collect()
     }
 }
 
@@ -142,6 +207,7 @@ impl Downloader {
 pub struct DownloadTask {
     pub url: String,
     pub destination: PathBuf,
+    pub expected_hash: Option<ExpectedHash>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,214 @@
+use cocoatly_core::config::{Config, RegistryEndpoint};
+use cocoatly_core::error::{CocoatlyError, Result};
+use cocoatly_core::registry::{PublishRequest, RegistryQuery, RegistryResponse, SearchQuery, SearchResult};
+use cocoatly_core::types::{PackageArtifact, PackageMetadata};
+use reqwest::{Client, RequestBuilder};
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Speaks to a `RegistryEndpoint` over HTTP, honoring the same
+/// `NetworkConfig` timeout/proxy settings as [`crate::Downloader`] plus the
+/// registry-specific `auth_tokens` and `reject_insecure_registries` knobs
+/// that only make sense once multiple registries are in play.
+pub struct RegistryClient {
+    client: Client,
+    config: Config,
+}
+
+impl RegistryClient {
+    pub fn new(config: Config) -> Result<Self> {
+        let mut client_builder = Client::builder()
+            .timeout(Duration::from_secs(config.network.timeout_seconds))
+            .connect_timeout(Duration::from_secs(30));
+
+        if config.network.use_proxy {
+            if let Some(proxy_url) = &config.network.proxy_url {
+                let proxy = reqwest::Proxy::all(proxy_url).map_err(|e| {
+                    CocoatlyError::RegistryError(format!("Failed to configure proxy: {}", e))
+                })?;
+                client_builder = client_builder.proxy(proxy);
+            }
+        }
+
+        let client = client_builder.build().map_err(|e| {
+            CocoatlyError::RegistryError(format!("Failed to create HTTP client: {}", e))
+        })?;
+
+        Ok(Self { client, config })
+    }
+
+    fn endpoint(&self, registry: &str) -> Result<&RegistryEndpoint> {
+        self.config.registry.registries.get(registry).ok_or_else(|| {
+            CocoatlyError::RegistryError(format!("Unknown registry: {}", registry))
+        })
+    }
+
+    /// Refuses to talk to a plain-HTTP URL when
+    /// `security.reject_insecure_registries` is set, so a misconfigured or
+    /// downgraded registry endpoint can't silently serve package data and
+    /// artifacts in the clear.
+    fn ensure_secure(&self, url: &str) -> Result<()> {
+        if self.config.security.reject_insecure_registries && !url.starts_with("https://") {
+            return Err(CocoatlyError::RegistryError(format!(
+                "refusing insecure (non-TLS) registry URL: {}",
+                url
+            )));
+        }
+        Ok(())
+    }
+
+    fn authorize(&self, registry: &str, endpoint: &RegistryEndpoint, request: RequestBuilder) -> RequestBuilder {
+        if endpoint.requires_auth {
+            if let Some(token) = self.config.registry.auth_tokens.get(registry) {
+                return request.bearer_auth(token);
+            }
+        }
+        request
+    }
+
+    /// Sends a request up to `retry_attempts` times in all (at least once),
+    /// with exponential backoff off `retry_delay_ms` (doubling after each
+    /// failed attempt), matching the knobs `NetworkConfig` exposes for
+    /// exactly this purpose.
+    async fn send_with_retry(&self, build: impl Fn() -> RequestBuilder) -> Result<reqwest::Response> {
+        let max_attempts = self.config.network.retry_attempts.max(1);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            let outcome = build().send().await;
+            match outcome {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) if attempt < max_attempts => {
+                    tracing::warn!(
+                        "Registry request returned {}, retrying (attempt {}/{})",
+                        response.status(),
+                        attempt,
+                        max_attempts
+                    );
+                }
+                Ok(response) => {
+                    return Err(CocoatlyError::RegistryError(format!(
+                        "Registry request failed with status {}",
+                        response.status()
+                    )));
+                }
+                Err(e) if attempt < max_attempts => {
+                    tracing::warn!(
+                        "Registry request error: {}, retrying (attempt {}/{})",
+                        e,
+                        attempt,
+                        max_attempts
+                    );
+                }
+                Err(e) => {
+                    return Err(CocoatlyError::RegistryError(format!(
+                        "Registry request failed: {}",
+                        e
+                    )));
+                }
+            }
+
+            let backoff_ms = self.config.network.retry_delay_ms.saturating_mul(1u64 << (attempt - 1));
+            sleep(Duration::from_millis(backoff_ms)).await;
+        }
+    }
+
+    pub async fn query(&self, registry: &str, query: &RegistryQuery) -> Result<RegistryResponse<PackageMetadata>> {
+        let endpoint = self.endpoint(registry)?;
+        let url = format!("{}/api/{}/packages/query", endpoint.url, endpoint.api_version);
+        self.ensure_secure(&url)?;
+
+        let response = self
+            .send_with_retry(|| self.authorize(registry, endpoint, self.client.post(&url).json(query)))
+            .await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| CocoatlyError::RegistryError(format!("Failed to parse registry response: {}", e)))
+    }
+
+    pub async fn search(&self, registry: &str, query: &SearchQuery) -> Result<RegistryResponse<SearchResult>> {
+        let endpoint = self.endpoint(registry)?;
+        let url = format!("{}/api/{}/packages/search", endpoint.url, endpoint.api_version);
+        self.ensure_secure(&url)?;
+
+        let response = self
+            .send_with_retry(|| self.authorize(registry, endpoint, self.client.get(&url).query(query)))
+            .await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| CocoatlyError::RegistryError(format!("Failed to parse registry response: {}", e)))
+    }
+
+    pub async fn publish(&self, registry: &str, request: &PublishRequest) -> Result<RegistryResponse<PackageMetadata>> {
+        let endpoint = self.endpoint(registry)?;
+        let url = format!("{}/api/{}/packages/publish", endpoint.url, endpoint.api_version);
+        self.ensure_secure(&url)?;
+
+        let response = self
+            .send_with_retry(|| self.authorize(registry, endpoint, self.client.post(&url).json(request)))
+            .await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| CocoatlyError::RegistryError(format!("Failed to parse registry response: {}", e)))
+    }
+
+    /// Downloads `artifact` into `destination`, preferring the registry's
+    /// acquire-by-hash path (`/api/<version>/objects/<algorithm>/<checksum>`)
+    /// over `artifact.download_url` — the same technique Debian's apt uses so
+    /// mirrors and caches can serve an immutable, content-addressed blob
+    /// instead of a path whose contents can change out from under a stale
+    /// index. Falls back to `download_url` when the registry doesn't expose
+    /// hash-addressed fetching (a 404, for instance).
+    pub async fn fetch_artifact(&self, registry: &str, artifact: &PackageArtifact, destination: &Path) -> Result<()> {
+        let endpoint = self.endpoint(registry)?;
+
+        let by_hash_url = format!(
+            "{}/api/{}/objects/{}/{}",
+            endpoint.url,
+            endpoint.api_version,
+            artifact.checksum_algorithm.as_str(),
+            artifact.checksum
+        );
+
+        match self.download_to(registry, endpoint, &by_hash_url, destination).await {
+            Ok(()) => Ok(()),
+            Err(_) => {
+                tracing::warn!(
+                    "Registry {} doesn't support acquire-by-hash, falling back to {}",
+                    registry,
+                    artifact.download_url
+                );
+                self.download_to(registry, endpoint, &artifact.download_url, destination).await
+            }
+        }
+    }
+
+    async fn download_to(&self, registry: &str, endpoint: &RegistryEndpoint, url: &str, destination: &Path) -> Result<()> {
+        self.ensure_secure(url)?;
+
+        let response = self
+            .send_with_retry(|| self.authorize(registry, endpoint, self.client.get(url)))
+            .await?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| CocoatlyError::RegistryError(format!("Failed to read artifact body: {}", e)))?;
+
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(destination, &bytes)?;
+
+        Ok(())
+    }
+}
@@ -126,6 +126,17 @@ impl FileSystemOps {
         Ok(metadata)
     }
 
+    pub fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(
+        original: P,
+        link: Q,
+    ) -> Result<()> {
+        if let Some(parent) = link.as_ref().parent() {
+            Self::ensure_directory(parent)?;
+        }
+        fs::hard_link(original.as_ref(), link.as_ref())?;
+        Ok(())
+    }
+
     pub fn create_symlink<P: AsRef<Path>, Q: AsRef<Path>>(
         original: P,
         link: Q,
@@ -0,0 +1,147 @@
+use cocoatly_core::{
+    types::{PackageArtifact, PackageName, InstalledPackage},
+    error::{CocoatlyError, Result},
+};
+use crate::install::{InstallContext, ExistingInstallOutcome, check_existing_install, cleanup_superseded_install, install_artifact};
+use crate::options::InstallOptions;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// One package to install as part of a batch, along with the names (within
+/// the same batch) it depends on so `install_packages` can schedule it after
+/// those finish.
+#[derive(Debug, Clone)]
+pub struct InstallJob {
+    pub artifact: PackageArtifact,
+    pub requested_by: Vec<PackageName>,
+    pub depends_on: Vec<PackageName>,
+    pub options: InstallOptions,
+}
+
+/// Installs `jobs` concurrently, respecting dependency order: the jobs are
+/// grouped into waves via Kahn's topological sort (each wave holds every job
+/// whose dependencies are satisfied by prior waves), and within a wave up to
+/// `max_concurrent` installs run at once as `tokio` tasks. `GlobalState` is
+/// only ever mutated by this function, once per wave, so concurrent installs
+/// never race on `state_file` writes.
+pub async fn install_packages(
+    context: InstallContext,
+    jobs: Vec<InstallJob>,
+    max_concurrent: usize,
+) -> Result<Vec<InstalledPackage>> {
+    let waves = compute_waves(&jobs)?;
+
+    let config = context.config.clone();
+    let mut state = context.state.clone();
+    let shared_context = Arc::new(context);
+    let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
+
+    let mut installed = Vec::new();
+
+    for wave in waves {
+        let mut handles = Vec::new();
+
+        for idx in wave {
+            let job = jobs[idx].clone();
+
+            // Applies --needed/--force before anything is spawned. Reads
+            // `state` only (any teardown of a superseded install is deferred
+            // until that job's install actually succeeds), so this is safe
+            // to run here, sequentially per job, ahead of the concurrent
+            // task below.
+            let supersedes = match check_existing_install(&state, &job.artifact, &job.options)? {
+                ExistingInstallOutcome::AlreadyInstalled(existing) => {
+                    installed.push(existing);
+                    continue;
+                }
+                ExistingInstallOutcome::Proceed { supersedes } => supersedes,
+            };
+
+            let context = Arc::clone(&shared_context);
+            let semaphore = Arc::clone(&semaphore);
+
+            let handle = tokio::spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("install semaphore should not be closed");
+                install_artifact(&context, &job.artifact, job.requested_by, &job.options).await
+            });
+
+            handles.push((handle, supersedes));
+        }
+
+        for (handle, supersedes) in handles {
+            let package = handle
+                .await
+                .map_err(|e| CocoatlyError::InstallationFailed(
+                    format!("Install task panicked: {}", e)
+                ))??;
+
+            state.add_package(package.clone());
+
+            if let Some(superseded) = &supersedes {
+                cleanup_superseded_install(&state, superseded, &package, &config.storage.install_root)?;
+            }
+
+            installed.push(package);
+        }
+
+        state.save_to_file(&config.storage.state_file)?;
+    }
+
+    Ok(installed)
+}
+
+/// Groups job indices into dependency-ordered waves via Kahn's algorithm:
+/// each wave is every not-yet-processed job whose dependencies (that are
+/// themselves part of this batch) have all been processed by a prior wave.
+fn compute_waves(jobs: &[InstallJob]) -> Result<Vec<Vec<usize>>> {
+    let index_of: HashMap<&str, usize> = jobs
+        .iter()
+        .enumerate()
+        .map(|(i, job)| (job.artifact.name.as_str(), i))
+        .collect();
+
+    let mut in_degree = vec![0usize; jobs.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); jobs.len()];
+
+    for (i, job) in jobs.iter().enumerate() {
+        for dep in &job.depends_on {
+            if let Some(&dep_idx) = index_of.get(dep.as_str()) {
+                in_degree[i] += 1;
+                dependents[dep_idx].push(i);
+            }
+            // Dependencies outside this batch are assumed already satisfied.
+        }
+    }
+
+    let mut processed = vec![false; jobs.len()];
+    let mut remaining = jobs.len();
+    let mut waves = Vec::new();
+
+    while remaining > 0 {
+        let wave: Vec<usize> = (0..jobs.len())
+            .filter(|&i| !processed[i] && in_degree[i] == 0)
+            .collect();
+
+        if wave.is_empty() {
+            return Err(CocoatlyError::DependencyResolutionFailed(
+                "Cycle detected in package dependency graph".to_string()
+            ));
+        }
+
+        for &i in &wave {
+            processed[i] = true;
+            remaining -= 1;
+            for &dependent in &dependents[i] {
+                in_degree[dependent] -= 1;
+            }
+        }
+
+        waves.push(wave);
+    }
+
+    Ok(waves)
+}
@@ -0,0 +1,107 @@
+use cocoatly_core::{
+    config::Config,
+    state::GlobalState,
+};
+use cocoatly_core::error::Result;
+use cocoatly_fs::FileSystemOps;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageHealth {
+    pub name: String,
+    pub version: String,
+    pub install_path: String,
+    pub install_path_exists: bool,
+    pub missing_files: Vec<String>,
+    pub manifest_parse_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkSummary {
+    pub max_concurrent_downloads: usize,
+    pub timeout_seconds: u64,
+    pub use_proxy: bool,
+    pub proxy_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageSummary {
+    pub install_root: String,
+    pub cache_dir: String,
+    pub state_file: String,
+    pub lock_file: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub healthy: bool,
+    pub packages: Vec<PackageHealth>,
+    pub network: NetworkSummary,
+    pub storage: StorageSummary,
+    /// Names of packages whose dangling pending operations were rolled back
+    /// by `--recover` before this report was generated. Empty when recovery
+    /// wasn't requested or there was nothing to recover.
+    pub recovered_packages: Vec<String>,
+}
+
+/// Inspects `state` and `config` and produces a one-shot health report: for
+/// every installed package, whether its install path and recorded files are
+/// still present, plus the active network/storage configuration. This is the
+/// data behind the `cocoatly-doctor` CLI.
+pub fn run_diagnostics(config: &Config, state: &GlobalState) -> Result<DiagnosticsReport> {
+    let mut packages = Vec::new();
+    let mut healthy = true;
+
+    for package in state.list_packages() {
+        let install_path = PathBuf::from(&package.install_path);
+        let install_path_exists = FileSystemOps::directory_exists(&install_path);
+
+        let missing_files: Vec<String> = package
+            .files
+            .iter()
+            .filter(|f| !FileSystemOps::file_exists(&f.path))
+            .map(|f| f.path.clone())
+            .collect();
+
+        let manifest_path = install_path.join("cocoatly.json");
+        let manifest_parse_error = if manifest_path.exists() {
+            cocoatly_core::manifest::load_manifest(&manifest_path)
+                .err()
+                .map(|e| e.to_string())
+        } else {
+            None
+        };
+
+        if !install_path_exists || !missing_files.is_empty() || manifest_parse_error.is_some() {
+            healthy = false;
+        }
+
+        packages.push(PackageHealth {
+            name: package.name.as_str().to_string(),
+            version: package.version.to_string(),
+            install_path: package.install_path.clone(),
+            install_path_exists,
+            missing_files,
+            manifest_parse_error,
+        });
+    }
+
+    Ok(DiagnosticsReport {
+        healthy,
+        packages,
+        network: NetworkSummary {
+            max_concurrent_downloads: config.network.max_concurrent_downloads,
+            timeout_seconds: config.network.timeout_seconds,
+            use_proxy: config.network.use_proxy,
+            proxy_url: config.network.proxy_url.clone(),
+        },
+        storage: StorageSummary {
+            install_root: config.storage.install_root.display().to_string(),
+            cache_dir: config.storage.cache_dir.display().to_string(),
+            state_file: config.storage.state_file.display().to_string(),
+            lock_file: config.storage.lock_file.display().to_string(),
+        },
+        recovered_packages: vec![],
+    })
+}
@@ -0,0 +1,135 @@
+use cocoatly_core::error::{CocoatlyError, Result};
+use std::path::Path;
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// Which lifecycle point a batch of hooks is running for, used only to label
+/// log output and error messages.
+#[derive(Debug, Clone, Copy)]
+pub enum HookPhase {
+    PreInstall,
+    PostInstall,
+    PreUninstall,
+    PostUninstall,
+    PostUpdate,
+}
+
+impl HookPhase {
+    fn label(&self) -> &'static str {
+        match self {
+            HookPhase::PreInstall => "pre-install",
+            HookPhase::PostInstall => "post-install",
+            HookPhase::PreUninstall => "pre-uninstall",
+            HookPhase::PostUninstall => "post-uninstall",
+            HookPhase::PostUpdate => "post-update",
+        }
+    }
+}
+
+/// One hook command's captured result, returned from [`run_hooks`] so the
+/// caller can fold it into its own record of the operation instead of only
+/// ever seeing the output in logs.
+#[derive(Debug, Clone)]
+pub struct HookExecution {
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+}
+
+/// Runs every command in `hooks` in order as `sh -c <command>`, with the
+/// package's name, version, install path, and the id of the operation the
+/// hook is running on behalf of exported as `COCOATLY_PKG_NAME`,
+/// `COCOATLY_PKG_VERSION`, `COCOATLY_INSTALL_PATH`, and
+/// `COCOATLY_OPERATION_ID`. The working directory is `install_path` when it
+/// exists, otherwise the current directory (hooks such as `pre_install` can
+/// run before the package's install directory has been created).
+///
+/// When `enabled` is false, every hook is skipped without being spawned
+/// (the `HooksConfig::enabled` fleet-wide switch or a per-install
+/// `InstallOptions::skip_hooks` override for an untrusted package) and an
+/// empty record is returned.
+///
+/// Stdout and stderr are logged through `tracing` and also captured into the
+/// returned `HookExecution` for each command that ran. When `fatal` is true,
+/// a non-zero exit aborts the remaining hooks in this batch and is returned
+/// as `CocoatlyError::HookExecutionFailed` (without the captured records) so
+/// the caller can fail (and roll back) its operation. When `fatal` is false
+/// the failure is logged as a warning and the remaining hooks still run.
+pub async fn run_hooks(
+    hooks: &[String],
+    phase: HookPhase,
+    name: &str,
+    version: &str,
+    install_path: &Path,
+    operation_id: Uuid,
+    enabled: bool,
+    fatal: bool,
+) -> Result<Vec<HookExecution>> {
+    if !enabled {
+        if !hooks.is_empty() {
+            tracing::debug!(
+                "Skipping {} configured {} hook(s): hooks disabled for this operation",
+                hooks.len(),
+                phase.label()
+            );
+        }
+        return Ok(Vec::new());
+    }
+
+    let mut executions = Vec::with_capacity(hooks.len());
+
+    for hook in hooks {
+        tracing::info!("Running {} hook: {}", phase.label(), hook);
+
+        let cwd: &Path = if install_path.exists() {
+            install_path
+        } else {
+            Path::new(".")
+        };
+
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(hook)
+            .current_dir(cwd)
+            .env("COCOATLY_PKG_NAME", name)
+            .env("COCOATLY_PKG_VERSION", version)
+            .env("COCOATLY_INSTALL_PATH", install_path.to_string_lossy().to_string())
+            .env("COCOATLY_OPERATION_ID", operation_id.to_string())
+            .output()
+            .await
+            .map_err(|e| CocoatlyError::HookExecutionFailed(
+                format!("Failed to spawn {} hook '{}': {}", phase.label(), hook, e)
+            ))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+
+        if !stdout.is_empty() {
+            tracing::info!("[{} hook] {}", phase.label(), stdout.trim_end());
+        }
+        if !stderr.is_empty() {
+            tracing::info!("[{} hook stderr] {}", phase.label(), stderr.trim_end());
+        }
+
+        let success = output.status.success();
+        executions.push(HookExecution {
+            command: hook.clone(),
+            stdout,
+            stderr,
+            exit_code: output.status.code(),
+            success,
+        });
+
+        if !success {
+            let message = format!("{} hook '{}' exited with {}", phase.label(), hook, output.status);
+            if fatal {
+                return Err(CocoatlyError::HookExecutionFailed(message));
+            }
+            tracing::warn!("{} (continuing)", message);
+        }
+    }
+
+    Ok(executions)
+}
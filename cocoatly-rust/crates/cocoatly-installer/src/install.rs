@@ -4,10 +4,15 @@ use cocoatly_core::{
     config::Config,
     state::GlobalState,
 };
-use cocoatly_downloader::{Downloader, DownloadTask};
+use cocoatly_downloader::Downloader;
 use cocoatly_compression::extract_archive;
-use cocoatly_crypto::verify_artifact;
+use cocoatly_crypto::verify_artifact as verify_artifact_integrity;
+use cocoatly_crypto::{ClearSignedManifest, Keyring};
+use cocoatly_crypto::verify_directory_against_checksum_table;
 use cocoatly_fs::FileSystemOps;
+use crate::hooks::{run_hooks, HookPhase};
+use crate::options::InstallOptions;
+use crate::store;
 use std::path::{Path, PathBuf};
 use chrono::Utc;
 use uuid::Uuid;
@@ -48,146 +53,414 @@ impl PackageInstaller {
         &mut self,
         artifact: &PackageArtifact,
         requested_by: Vec<PackageName>,
+        options: InstallOptions,
     ) -> Result<InstalledPackage> {
-        let operation_id = Uuid::new_v4();
-        let started_at = Utc::now();
+        let outcome = match check_existing_install(&self.context.state, artifact, &options)? {
+            ExistingInstallOutcome::AlreadyInstalled(existing) => return Ok(existing),
+            ExistingInstallOutcome::Proceed { supersedes } => supersedes,
+        };
+
+        let installed_package = install_artifact(&self.context, artifact, requested_by, &options).await?;
+
+        self.context.state.add_package(installed_package.clone());
+
+        if let Some(superseded) = &outcome {
+            cleanup_superseded_install(
+                &self.context.state,
+                superseded,
+                &installed_package,
+                &self.context.config.storage.install_root,
+            )?;
+        }
+
+        self.context.state.save_to_file(&self.context.config.storage.state_file)?;
 
+        Ok(installed_package)
+    }
+}
+
+/// What `check_existing_install` found and what the caller should do about it.
+pub(crate) enum ExistingInstallOutcome {
+    /// `--needed` means the install should be skipped entirely; here's the
+    /// already-installed package to return as-is.
+    AlreadyInstalled(InstalledPackage),
+    /// The caller should proceed with `install_artifact`. If `supersedes` is
+    /// set, that's the previously-installed version being replaced — the
+    /// caller must clean up its install directory and store references
+    /// (via `cleanup_superseded_install`) only *after* the new version has
+    /// installed successfully, so a failed install leaves the old version
+    /// intact instead of uninstalled.
+    Proceed { supersedes: Option<InstalledPackage> },
+}
+
+/// Checks whether `artifact` is already installed and applies
+/// `--needed`/`--force` semantics before a caller proceeds to
+/// `install_artifact`. Doesn't touch `state` or disk itself — every case
+/// that requires tearing down an existing install defers that to
+/// `cleanup_superseded_install`, called by the caller only once the new
+/// version is confirmed installed. Shared by `PackageInstaller::install` and
+/// the `install_packages` batch path so `--needed`/`--force` behave
+/// identically on both.
+///
+/// `GlobalState.installed_packages` holds at most one `InstalledPackage` per
+/// `PackageName` — it does not track versions side by side. So when a
+/// *different* version is already installed, this always supersedes it
+/// (rather than erroring, which only applies to a same-version conflict);
+/// otherwise the old version's directory would be silently orphaned on disk
+/// once its state entry is overwritten by the new one.
+pub(crate) fn check_existing_install(
+    state: &GlobalState,
+    artifact: &PackageArtifact,
+    options: &InstallOptions,
+) -> Result<ExistingInstallOutcome> {
+    let Some(existing) = state.get_package(&artifact.name) else {
+        return Ok(ExistingInstallOutcome::Proceed { supersedes: None });
+    };
+
+    if existing.version != artifact.version {
         tracing::info!(
-            "Installing package {} version {}",
+            "Replacing installed version {} of {} with {}",
+            existing.version.to_string(),
             artifact.name.as_str(),
             artifact.version.to_string()
         );
+        return Ok(ExistingInstallOutcome::Proceed { supersedes: Some(existing.clone()) });
+    }
 
-        if self.context.state.has_package(&artifact.name, &artifact.version) {
-            return Err(CocoatlyError::InstallationFailed(
-                format!("Package {} {} already installed", artifact.name.as_str(), artifact.version.to_string())
-            ));
-        }
-
-        let archive_path = self.download_artifact(artifact).await?;
+    if options.is_needed() {
+        tracing::info!(
+            "Package {} {} already installed, skipping install (--needed)",
+            artifact.name.as_str(),
+            artifact.version.to_string()
+        );
+        return Ok(ExistingInstallOutcome::AlreadyInstalled(existing.clone()));
+    }
 
-        self.verify_artifact(&archive_path, artifact)?;
+    if !options.is_force() {
+        return Err(CocoatlyError::InstallationFailed(
+            format!("Package {} {} already installed", artifact.name.as_str(), artifact.version.to_string())
+        ));
+    }
 
-        let install_path = self.extract_and_install(&archive_path, artifact).await?;
+    tracing::warn!(
+        "Forcing reinstall of {} {}",
+        artifact.name.as_str(),
+        artifact.version.to_string()
+    );
+    Ok(ExistingInstallOutcome::Proceed { supersedes: Some(existing.clone()) })
+}
 
-        let files = FileSystemOps::list_files(&install_path)?
-            .into_iter()
-            .map(|p| p.to_string_lossy().to_string())
-            .collect();
+/// Removes a superseded install's on-disk directory and garbage-collects any
+/// of its store objects no longer referenced by another installed package.
+/// Called only after the version replacing `superseded` has itself installed
+/// successfully, so a failed install/reinstall never leaves the package
+/// worse off than before the attempt.
+///
+/// `new_package` is the install that replaced `superseded` (same name, new
+/// version). `reference_count` (which `store::remove_unreferenced` consults)
+/// only excludes `superseded.name`'s *current* state entry from the count —
+/// since `new_package` is installed under that same name, any store object
+/// `superseded` and `new_package` happen to share (e.g. an unchanged file
+/// across the version bump) would otherwise look unreferenced and get
+/// garbage-collected out from under the install that still needs it,
+/// regardless of whether this runs before or after `state.add_package`. So
+/// `new_package`'s own file hashes are excluded from the candidate set
+/// up front, independent of that ordering.
+pub(crate) fn cleanup_superseded_install(
+    state: &GlobalState,
+    superseded: &InstalledPackage,
+    new_package: &InstalledPackage,
+    install_root: &Path,
+) -> Result<()> {
+    let install_path = PathBuf::from(&superseded.install_path);
+    if install_path.exists() {
+        FileSystemOps::remove_directory(&install_path)?;
+    }
 
-        let installed_package = InstalledPackage {
-            id: Uuid::new_v4(),
-            name: artifact.name.clone(),
-            version: artifact.version.clone(),
-            install_path: install_path.to_string_lossy().to_string(),
-            installed_at: Utc::now(),
-            requested_by,
-            files,
-            checksum: artifact.checksum.clone(),
-        };
+    let kept_hashes: std::collections::HashSet<&str> = new_package.files.iter()
+        .map(|f| f.hash.as_str())
+        .collect();
 
-        self.cleanup_temp_files(&archive_path)?;
+    let hashes: Vec<String> = superseded.files.iter()
+        .map(|f| f.hash.clone())
+        .filter(|hash| !kept_hashes.contains(hash.as_str()))
+        .collect();
 
-        self.context.state.add_package(installed_package.clone());
-        self.context.state.save_to_file(&self.context.config.storage.state_file)?;
+    store::remove_unreferenced(install_root, state, &hashes, &superseded.name)?;
 
-        self.run_post_install_hooks(&installed_package)?;
+    Ok(())
+}
 
-        tracing::info!(
-            "Successfully installed package {} version {}",
+/// Downloads, verifies, and extracts `artifact` into its install directory and
+/// builds the resulting `InstalledPackage` record, without touching shared
+/// `GlobalState` — callers own committing the result (a single install does
+/// it immediately; `install_packages` batches commits per dependency wave).
+///
+/// `pre_install` hooks run before anything is downloaded (always fatal on
+/// failure) and `post_install` hooks run once the package is fully extracted
+/// (fatal only when `hooks.fail_on_post_hook_error` is set). A fatal
+/// `post_install` failure rolls the extraction back by removing the install
+/// directory before the error is returned; a non-fatal one is only logged
+/// and the install still succeeds.
+pub(crate) async fn install_artifact(
+    context: &InstallContext,
+    artifact: &PackageArtifact,
+    requested_by: Vec<PackageName>,
+    options: &InstallOptions,
+) -> Result<InstalledPackage> {
+    tracing::info!(
+        "Installing package {} version {}",
+        artifact.name.as_str(),
+        artifact.version.to_string()
+    );
+
+    let operation_id = Uuid::new_v4();
+    let hooks_enabled = context.config.hooks.enabled && !options.is_skip_hooks();
+
+    let prospective_install_path = context.config.storage.install_root
+        .join(artifact.name.as_str())
+        .join(artifact.version.to_string());
+
+    if let Err(e) = run_hooks(
+        &context.config.hooks.pre_install,
+        HookPhase::PreInstall,
+        artifact.name.as_str(),
+        &artifact.version.to_string(),
+        &prospective_install_path,
+        operation_id,
+        hooks_enabled,
+        true,
+    ).await {
+        // The install never started, but post-install cleanup hooks still
+        // get a chance to undo anything the (now-aborted) attempt left
+        // lying around, e.g. a service registration a pre-install hook
+        // itself performed before failing partway through.
+        let _ = run_hooks(
+            &context.config.hooks.post_install,
+            HookPhase::PostInstall,
             artifact.name.as_str(),
-            artifact.version.to_string()
-        );
+            &artifact.version.to_string(),
+            &prospective_install_path,
+            operation_id,
+            hooks_enabled,
+            false,
+        ).await;
+        return Err(e);
+    }
 
-        Ok(installed_package)
+    let archive_path = download_artifact(context, artifact).await?;
+
+    verify_downloaded_artifact(context, &archive_path, artifact, options)?;
+
+    let (install_path, files) = extract_and_install(context, &archive_path, artifact).await?;
+
+    if let Err(e) = verify_signed_manifest(context, &install_path, artifact, options) {
+        FileSystemOps::remove_directory(&install_path)?;
+        return Err(e);
     }
 
-    async fn download_artifact(&self, artifact: &PackageArtifact) -> Result<PathBuf> {
-        let filename = format!(
-            "{}-{}.tar.gz",
+    // A dependency-pulled-in install keeps its actual requesters so it can
+    // later be garbage-collected as an orphan once they're all gone; a
+    // direct user install has no requester and is never orphan-collected.
+    let requested_by = if options.is_as_dependency() { requested_by } else { vec![] };
+
+    let installed_package = InstalledPackage {
+        id: Uuid::new_v4(),
+        name: artifact.name.clone(),
+        version: artifact.version.clone(),
+        install_path: install_path.to_string_lossy().to_string(),
+        installed_at: Utc::now(),
+        requested_by,
+        files,
+        checksum: artifact.checksum.clone(),
+        checksum_algorithm: artifact.checksum_algorithm.clone(),
+        download_url: artifact.download_url.clone(),
+    };
+
+    cleanup_temp_file(&archive_path)?;
+
+    if let Err(e) = run_hooks(
+        &context.config.hooks.post_install,
+        HookPhase::PostInstall,
+        artifact.name.as_str(),
+        &artifact.version.to_string(),
+        &install_path,
+        operation_id,
+        hooks_enabled,
+        context.config.hooks.fail_on_post_hook_error,
+    ).await {
+        tracing::error!(
+            "post-install hook failed for {} {}, rolling back: {}",
             artifact.name.as_str(),
-            artifact.version.to_string()
+            artifact.version.to_string(),
+            e
         );
+        FileSystemOps::remove_directory(&install_path)?;
+        return Err(e);
+    }
+
+    tracing::info!(
+        "Successfully installed package {} version {}",
+        artifact.name.as_str(),
+        artifact.version.to_string()
+    );
+
+    Ok(installed_package)
+}
 
-        let destination = self.context.temp_dir.join(&filename);
+async fn download_artifact(context: &InstallContext, artifact: &PackageArtifact) -> Result<PathBuf> {
+    let filename = format!(
+        "{}-{}.tar.gz",
+        artifact.name.as_str(),
+        artifact.version.to_string()
+    );
 
-        tracing::info!("Downloading artifact from {}", artifact.download_url);
+    let destination = context.temp_dir.join(&filename);
 
-        self.context
-            .downloader
-            .download(&artifact.download_url, &destination, None)
-            .await?;
+    tracing::info!("Downloading artifact from {}", artifact.download_url);
 
-        Ok(destination)
+    context
+        .downloader
+        .download(&artifact.download_url, &destination, None)
+        .await?;
+
+    Ok(destination)
+}
+
+fn verify_downloaded_artifact(
+    context: &InstallContext,
+    path: &Path,
+    artifact: &PackageArtifact,
+    options: &InstallOptions,
+) -> Result<()> {
+    if options.is_skip_verify() {
+        tracing::warn!("Checksum verification skipped (--skip-verify)");
+        return Ok(());
     }
 
-    fn verify_artifact(&self, path: &Path, artifact: &PackageArtifact) -> Result<()> {
-        if !self.context.config.security.verify_checksums {
-            tracing::warn!("Checksum verification disabled");
-            return Ok(());
-        }
+    if !context.config.security.verify_checksums {
+        tracing::warn!("Checksum verification disabled");
+        return Ok(());
+    }
+
+    tracing::info!("Verifying artifact integrity");
 
-        tracing::info!("Verifying artifact integrity");
+    let keyring = Keyring::from_hex_keys(&context.config.security.trusted_keys);
 
-        let public_key = None;
+    if let Some(key_id) = verify_artifact_integrity(path, artifact, &keyring)? {
+        tracing::info!("Artifact signature verified against trusted key {}", key_id);
+    }
 
-        verify_artifact(path, artifact, public_key)?;
+    Ok(())
+}
 
-        Ok(())
+/// If `artifact` carries a `signed_manifest`, verifies its signature against
+/// `keyring` and then checks every file it lists against what was just
+/// extracted into `install_path`: one clear-signed checksum table
+/// transitively authenticates every extracted file, rather than only the
+/// archive blob `verify_downloaded_artifact` already checked. A no-op when
+/// the artifact carries no manifest, verification is skipped, or `keyring`
+/// holds no keys, matching how `verify_downloaded_artifact` treats the
+/// archive-level signature as opportunistic.
+fn verify_signed_manifest(
+    context: &InstallContext,
+    install_path: &Path,
+    artifact: &PackageArtifact,
+    options: &InstallOptions,
+) -> Result<()> {
+    if options.is_skip_verify() || !context.config.security.verify_checksums {
+        return Ok(());
     }
 
-    async fn extract_and_install(
-        &self,
-        archive_path: &Path,
-        artifact: &PackageArtifact,
-    ) -> Result<PathBuf> {
-        let extract_dir = self.context.temp_dir.join(format!(
-            "extract-{}",
-            Uuid::new_v4()
-        ));
+    let Some(envelope) = &artifact.signed_manifest else {
+        return Ok(());
+    };
 
-        FileSystemOps::ensure_directory(&extract_dir)?;
+    let keyring = Keyring::from_hex_keys(&context.config.security.trusted_keys);
+    if keyring.is_empty() {
+        return Ok(());
+    }
 
-        tracing::info!("Extracting archive");
+    let signed_manifest = ClearSignedManifest::parse(envelope)?;
+    let (checksum_table, key_id) = signed_manifest.verify(&keyring)?;
 
-        extract_archive(archive_path, &extract_dir)?;
+    verify_directory_against_checksum_table(install_path, &checksum_table, &artifact.checksum_algorithm)?;
 
-        let install_root = &self.context.config.storage.install_root;
-        let package_install_dir = install_root
-            .join(artifact.name.as_str())
-            .join(artifact.version.to_string());
+    tracing::info!("Per-file manifest signature verified against trusted key {}", key_id);
 
-        FileSystemOps::ensure_directory(&package_install_dir)?;
+    Ok(())
+}
 
-        tracing::info!("Installing to {}", package_install_dir.display());
+/// Extracts the archive and populates the per-version install directory from
+/// the content-addressed store under `install_root/.store`: each extracted
+/// file is written into the store keyed by its BLAKE3 digest (skipped if that
+/// digest is already present, e.g. an identical file from another version of
+/// this or any other package) and the install directory gets a hard link
+/// (falling back to a symlink, then a copy) to the store object instead of
+/// its own copy of the bytes.
+async fn extract_and_install(
+    context: &InstallContext,
+    archive_path: &Path,
+    artifact: &PackageArtifact,
+) -> Result<(PathBuf, Vec<StoredFile>)> {
+    let extract_dir = context.temp_dir.join(format!(
+        "extract-{}",
+        Uuid::new_v4()
+    ));
 
-        FileSystemOps::copy_directory(&extract_dir, &package_install_dir)?;
+    FileSystemOps::ensure_directory(&extract_dir)?;
 
-        FileSystemOps::remove_directory(&extract_dir)?;
+    tracing::info!("Extracting archive");
 
-        Ok(package_install_dir)
-    }
+    extract_archive(archive_path, &extract_dir)?;
 
-    fn cleanup_temp_files(&self, archive_path: &Path) -> Result<()> {
-        if archive_path.exists() {
-            std::fs::remove_file(archive_path)?;
-        }
-        Ok(())
+    let install_root = &context.config.storage.install_root;
+    let package_install_dir = install_root
+        .join(artifact.name.as_str())
+        .join(artifact.version.to_string());
+
+    FileSystemOps::ensure_directory(&package_install_dir)?;
+
+    tracing::info!("Installing to {} via content-addressed store", package_install_dir.display());
+
+    let mut files = Vec::new();
+
+    for source in FileSystemOps::list_files(&extract_dir)? {
+        let relative = source.strip_prefix(&extract_dir).map_err(|e| {
+            CocoatlyError::InstallationFailed(format!("Extracted file outside extract dir: {}", e))
+        })?;
+        let target = package_install_dir.join(relative);
+
+        let size = std::fs::metadata(&source)?.len();
+        let hash = store::store_file(install_root, &source)?;
+        store::link_from_store(install_root, &hash, &target)?;
+
+        files.push(StoredFile {
+            path: target.to_string_lossy().to_string(),
+            hash,
+            size,
+            algorithm: HashAlgorithm::Blake3,
+        });
     }
 
-    fn run_post_install_hooks(&self, package: &InstalledPackage) -> Result<()> {
-        for hook in &self.context.config.hooks.post_install {
-            tracing::info!("Running post-install hook: {}", hook);
-        }
-        Ok(())
+    FileSystemOps::remove_directory(&extract_dir)?;
+
+    Ok((package_install_dir, files))
+}
+
+fn cleanup_temp_file(archive_path: &Path) -> Result<()> {
+    if archive_path.exists() {
+        std::fs::remove_file(archive_path)?;
     }
+    Ok(())
 }
 
 pub async fn install_package(
     context: InstallContext,
     artifact: &PackageArtifact,
     requested_by: Vec<PackageName>,
+    options: InstallOptions,
 ) -> Result<InstalledPackage> {
     let mut installer = PackageInstaller::new(context);
-    installer.install(artifact, requested_by).await
+    installer.install(artifact, requested_by, options).await
 }
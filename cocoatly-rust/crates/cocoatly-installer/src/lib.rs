@@ -1,9 +1,22 @@
+pub mod hooks;
+pub mod options;
+pub mod store;
 pub mod install;
 pub mod uninstall;
 pub mod update;
 pub mod verify;
+pub mod doctor;
+pub mod batch;
+pub mod lockfile_install;
+pub mod pack;
 
+pub use hooks::{run_hooks, HookPhase};
+pub use options::InstallOptions;
 pub use install::{PackageInstaller, InstallContext, install_package};
 pub use uninstall::{PackageUninstaller, uninstall_package};
 pub use update::{PackageUpdater, update_package};
 pub use verify::{verify_installation, repair_package};
+pub use doctor::{run_diagnostics, DiagnosticsReport};
+pub use batch::{InstallJob, install_packages};
+pub use lockfile_install::install_from_lockfile;
+pub use pack::{PackOptions, build_package_artifact};
@@ -0,0 +1,65 @@
+use cocoatly_core::{
+    types::{PackageArtifact, CompressionCodec},
+    lockfile::{Lockfile, LockedPackage},
+    error::Result,
+    config::Config,
+    state::GlobalState,
+};
+use crate::install::InstallContext;
+use crate::batch::{InstallJob, install_packages};
+use crate::options::InstallOptions;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Reads a lockfile from `path` and installs exactly what it pins, skipping
+/// registry resolution entirely: every `LockedPackage` is turned straight
+/// into a `PackageArtifact` (its `checksum` set to the locked hash, in
+/// `locked_package_to_job` below) and handed to `install_packages`, which
+/// performs the usual download/verify/extract pipeline and so fails hard
+/// with `CocoatlyError::HashMismatch` if the artifact's recomputed checksum
+/// doesn't match the value the package was locked to. This reuses the
+/// generic artifact-checksum enforcement every install already goes
+/// through rather than a separate lockfile-specific check.
+pub async fn install_from_lockfile<P: AsRef<Path>>(
+    path: P,
+    config: Config,
+    state: GlobalState,
+    max_concurrent: usize,
+) -> Result<Vec<cocoatly_core::types::InstalledPackage>> {
+    let lockfile = Lockfile::load_from_file(path)?;
+
+    let jobs = lockfile
+        .packages
+        .iter()
+        .map(locked_package_to_job)
+        .collect();
+
+    let context = InstallContext::new(config, state)?;
+
+    install_packages(context, jobs, max_concurrent).await
+}
+
+fn locked_package_to_job(locked: &LockedPackage) -> InstallJob {
+    InstallJob {
+        artifact: PackageArtifact {
+            package_id: Uuid::new_v4(),
+            name: locked.name.clone(),
+            version: locked.version.clone(),
+            download_url: locked.url.clone(),
+            checksum: locked.hash.clone(),
+            checksum_algorithm: locked.hash_algorithm.clone(),
+            signature: None,
+            size: 0,
+            // The lockfile doesn't pin a codec; decompression auto-detects
+            // from the archive's magic bytes so this is never consulted.
+            compression_codec: CompressionCodec::Gzip,
+            compression_level: 6,
+            // The lockfile pins a single flat checksum per package, not a
+            // per-file table, so there's no clear-signed manifest to carry.
+            signed_manifest: None,
+        },
+        requested_by: vec![],
+        depends_on: vec![],
+        options: InstallOptions::new(),
+    }
+}
@@ -0,0 +1,77 @@
+/// Fluent flag builder controlling how `PackageInstaller::install` and
+/// `PackageUpdater::update` handle an install that's already satisfied,
+/// conflicts with an existing version, or is being pulled in as another
+/// package's dependency — mirrors makepkg-style flag builders
+/// (`InstallOptions::new().needed(true).force(true)`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstallOptions {
+    needed: bool,
+    force: bool,
+    as_dependency: bool,
+    skip_verify: bool,
+    skip_hooks: bool,
+}
+
+impl InstallOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If the exact version is already installed, return it as a no-op
+    /// instead of failing with "already installed".
+    pub fn needed(mut self, needed: bool) -> Self {
+        self.needed = needed;
+        self
+    }
+
+    /// Allow reinstalling over an already-installed version by removing the
+    /// old install directory first, instead of failing with "already installed".
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Record the package as pulled in by its dependents (`requested_by`
+    /// kept as given) rather than as a direct user install (`requested_by`
+    /// forced empty), so it can later be garbage-collected as an orphan.
+    pub fn as_dependency(mut self, as_dependency: bool) -> Self {
+        self.as_dependency = as_dependency;
+        self
+    }
+
+    /// Skip checksum verification for this install regardless of
+    /// `config.security.verify_checksums`.
+    pub fn skip_verify(mut self, skip_verify: bool) -> Self {
+        self.skip_verify = skip_verify;
+        self
+    }
+
+    /// Skip pre/post-install hooks for this install regardless of
+    /// `config.hooks.enabled`, e.g. for a package pulled from an untrusted
+    /// or unverified source where running arbitrary shell commands on its
+    /// behalf isn't warranted.
+    pub fn skip_hooks(mut self, skip_hooks: bool) -> Self {
+        self.skip_hooks = skip_hooks;
+        self
+    }
+
+    pub fn is_needed(&self) -> bool {
+        self.needed
+    }
+
+    pub fn is_force(&self) -> bool {
+        self.force
+    }
+
+    pub fn is_as_dependency(&self) -> bool {
+        self.as_dependency
+    }
+
+    pub fn is_skip_verify(&self) -> bool {
+        self.skip_verify
+    }
+
+    pub fn is_skip_hooks(&self) -> bool {
+        self.skip_hooks
+    }
+}
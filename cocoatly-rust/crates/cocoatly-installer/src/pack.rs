@@ -0,0 +1,165 @@
+use cocoatly_core::{
+    types::{PackageArtifact, PackageManifest, CompressionCodec, HashAlgorithm},
+    error::{CocoatlyError, Result},
+};
+use cocoatly_compression::build_archive_deterministic;
+use cocoatly_crypto::{compute_file_hash, sign_data, ClearSignedManifest};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Options controlling how `build_package_artifact` packs a source
+/// directory into a distributable artifact.
+pub struct PackOptions {
+    codec: CompressionCodec,
+    level: i32,
+    checksum_algorithm: HashAlgorithm,
+    signing_key: Option<Vec<u8>>,
+}
+
+impl PackOptions {
+    pub fn new() -> Self {
+        Self {
+            codec: CompressionCodec::Gzip,
+            level: 9,
+            checksum_algorithm: HashAlgorithm::Blake3,
+            signing_key: None,
+        }
+    }
+
+    pub fn codec(mut self, codec: CompressionCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub fn level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn checksum_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.checksum_algorithm = algorithm;
+        self
+    }
+
+    /// Signs the artifact's checksum with `private_key` (PKCS8-encoded
+    /// Ed25519, the format `cocoatly_crypto::signature::generate_keypair` produces).
+    pub fn sign_with(mut self, private_key: Vec<u8>) -> Self {
+        self.signing_key = Some(private_key);
+        self
+    }
+}
+
+impl Default for PackOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Packs `source_dir` into `output_file` and returns a fully populated
+/// `PackageArtifact` ready to publish: a deterministic archive (fixed tar
+/// headers, sorted member order, reserved/unsafe paths refused), a checksum
+/// over the packed bytes, an optional detached signature over that
+/// checksum, and a verify-on-pack round trip that decompresses the archive
+/// into a temp directory and re-hashes every file against its source-tree
+/// original to catch non-reproducible or corrupt output before publish.
+pub fn build_package_artifact(
+    source_dir: &Path,
+    manifest: &PackageManifest,
+    download_url: String,
+    output_file: &Path,
+    options: &PackOptions,
+) -> Result<PackageArtifact> {
+    let (size, entries) = build_archive_deterministic(
+        source_dir,
+        output_file,
+        &options.codec,
+        options.level,
+    )?;
+
+    verify_round_trip(source_dir, output_file, &entries)?;
+
+    let checksum = compute_file_hash(output_file, &options.checksum_algorithm)?;
+
+    let signature = match &options.signing_key {
+        Some(private_key) => Some(hex::encode(sign_data(private_key, checksum.as_bytes())?)),
+        None => None,
+    };
+
+    let signed_manifest = match &options.signing_key {
+        Some(private_key) => Some(build_signed_manifest(
+            source_dir,
+            &entries,
+            &options.checksum_algorithm,
+            private_key,
+        )?),
+        None => None,
+    };
+
+    Ok(PackageArtifact {
+        package_id: manifest.package.id,
+        name: manifest.package.name.clone(),
+        version: manifest.package.version.clone(),
+        download_url,
+        checksum,
+        checksum_algorithm: options.checksum_algorithm.clone(),
+        signature,
+        size,
+        compression_codec: options.codec.clone(),
+        compression_level: options.level,
+        signed_manifest,
+    })
+}
+
+/// Builds the per-file checksum table for every packed entry (same shape
+/// `verify_directory_against_checksum_table` expects) and clear-signs it with
+/// `signing_key`, so the resulting envelope's single signature transitively
+/// authenticates every file the archive unpacks to.
+fn build_signed_manifest(
+    source_dir: &Path,
+    entries: &[PathBuf],
+    algorithm: &HashAlgorithm,
+    signing_key: &[u8],
+) -> Result<String> {
+    let mut manifest_text = String::new();
+    for relative in entries {
+        let hash = compute_file_hash(source_dir.join(relative), algorithm)?;
+        manifest_text.push_str(&format!("{} {}\n", hash, relative.display()));
+    }
+
+    let signature = sign_data(signing_key, manifest_text.trim().as_bytes())?;
+    Ok(ClearSignedManifest::encode(&manifest_text, &signature))
+}
+
+/// Decompresses the just-built archive into a fresh temp directory and
+/// re-hashes every packed file against its source-tree original, failing
+/// the pack if any file didn't round-trip byte-identical.
+fn verify_round_trip(source_dir: &Path, archive_path: &Path, entries: &[PathBuf]) -> Result<()> {
+    let temp_dir = std::env::temp_dir().join(format!("cocoatly-pack-verify-{}", Uuid::new_v4()));
+
+    cocoatly_compression::extract_archive(archive_path, &temp_dir)?;
+
+    let result = (|| -> Result<()> {
+        for relative in entries {
+            let original = source_dir.join(relative);
+            let extracted = temp_dir.join(relative);
+
+            let original_hash = compute_file_hash(&original, &HashAlgorithm::Blake3)?;
+            let extracted_hash = compute_file_hash(&extracted, &HashAlgorithm::Blake3).map_err(|_| {
+                CocoatlyError::VerificationFailed(
+                    format!("verify-on-pack: {} missing after round-trip decompress", relative.display())
+                )
+            })?;
+
+            if original_hash != extracted_hash {
+                return Err(CocoatlyError::VerificationFailed(
+                    format!("verify-on-pack: {} did not round-trip identically", relative.display())
+                ));
+            }
+        }
+        Ok(())
+    })();
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    result
+}
@@ -0,0 +1,101 @@
+use cocoatly_core::{
+    types::{HashAlgorithm, PackageName},
+    error::Result,
+    state::GlobalState,
+};
+use cocoatly_crypto::hash::compute_file_hash;
+use cocoatly_fs::FileSystemOps;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Path to the content-addressed object for `hash` under
+/// `install_root/.store`, sharded by a short prefix of the digest so a
+/// single directory doesn't end up holding every installed file.
+pub fn object_path(install_root: &Path, hash: &str) -> PathBuf {
+    let prefix = &hash[..hash.len().min(2)];
+    install_root.join(".store").join(prefix).join(hash)
+}
+
+/// Content-addresses `source` into the store keyed by its BLAKE3 digest,
+/// writing the object only the first time this digest is seen, and returns
+/// the digest.
+pub fn store_file(install_root: &Path, source: &Path) -> Result<String> {
+    let hash = compute_file_hash(source, &HashAlgorithm::Blake3)?;
+    let object = object_path(install_root, &hash);
+
+    if !object.exists() {
+        if let Some(parent) = object.parent() {
+            FileSystemOps::ensure_directory(parent)?;
+        }
+        std::fs::copy(source, &object)?;
+    }
+
+    Ok(hash)
+}
+
+/// Populates `target` from the store object for `hash`: a hard link when
+/// possible, falling back to a symlink (e.g. the store and the install root
+/// sit on different devices and disallow cross-device hard links), and
+/// finally a plain copy.
+pub fn link_from_store(install_root: &Path, hash: &str, target: &Path) -> Result<()> {
+    let object = object_path(install_root, hash);
+
+    if let Some(parent) = target.parent() {
+        FileSystemOps::ensure_directory(parent)?;
+    }
+
+    if FileSystemOps::hard_link(&object, target).is_ok() {
+        return Ok(());
+    }
+
+    if FileSystemOps::create_symlink(&object, target).is_ok() {
+        return Ok(());
+    }
+
+    std::fs::copy(&object, target)?;
+    Ok(())
+}
+
+/// Counts how many installed packages other than `excluding` still reference
+/// `hash` through one of their stored files.
+fn reference_count(state: &GlobalState, hash: &str, excluding: &PackageName) -> usize {
+    state
+        .list_packages()
+        .into_iter()
+        .filter(|pkg| &pkg.name != excluding)
+        .filter(|pkg| pkg.files.iter().any(|f| f.hash == hash))
+        .count()
+}
+
+/// Deletes the store object for `hash` once no installed package other than
+/// `excluding` (the package currently being uninstalled) still references it.
+pub fn remove_if_unreferenced(
+    install_root: &Path,
+    state: &GlobalState,
+    hash: &str,
+    excluding: &PackageName,
+) -> Result<()> {
+    if reference_count(state, hash, excluding) == 0 {
+        let object = object_path(install_root, hash);
+        if object.exists() {
+            std::fs::remove_file(object)?;
+        }
+    }
+    Ok(())
+}
+
+/// Same as `remove_if_unreferenced`, but for every distinct hash in `hashes`
+/// at once (a package can reference the same store object from more than
+/// one file).
+pub fn remove_unreferenced(
+    install_root: &Path,
+    state: &GlobalState,
+    hashes: &[String],
+    excluding: &PackageName,
+) -> Result<()> {
+    let unique: HashSet<&String> = hashes.iter().collect();
+    for hash in unique {
+        remove_if_unreferenced(install_root, state, hash, excluding)?;
+    }
+    Ok(())
+}
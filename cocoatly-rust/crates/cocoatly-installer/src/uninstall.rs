@@ -5,7 +5,10 @@ use cocoatly_core::{
     state::GlobalState,
 };
 use cocoatly_fs::FileSystemOps;
+use crate::hooks::{run_hooks, HookPhase};
+use crate::store;
 use std::path::PathBuf;
+use uuid::Uuid;
 
 pub struct PackageUninstaller {
     config: Config,
@@ -17,7 +20,7 @@ impl PackageUninstaller {
         Self { config, state }
     }
 
-    pub fn uninstall(&mut self, name: &PackageName) -> Result<()> {
+    pub async fn uninstall(&mut self, name: &PackageName) -> Result<()> {
         tracing::info!("Uninstalling package {}", name.as_str());
 
         let package = self.state
@@ -25,14 +28,23 @@ impl PackageUninstaller {
             .ok_or_else(|| CocoatlyError::PackageNotFound(name.as_str().to_string()))?
             .clone();
 
-        self.run_pre_uninstall_hooks(&package)?;
+        let operation_id = Uuid::new_v4();
+
+        if let Err(e) = self.run_pre_uninstall_hooks(&package, operation_id).await {
+            // The uninstall never started, but post-uninstall cleanup hooks
+            // still get a chance to run, matching the install-side
+            // behavior: a failing pre-hook aborts the operation without
+            // skipping whatever cleanup the post-hook was meant to do.
+            let _ = self.run_post_uninstall_hooks(&package, operation_id, false).await;
+            return Err(e);
+        }
 
         self.remove_package_files(&package)?;
 
         self.state.remove_package(name);
         self.state.save_to_file(&self.config.storage.state_file)?;
 
-        self.run_post_uninstall_hooks(&package)?;
+        self.run_post_uninstall_hooks(&package, operation_id, self.config.hooks.fail_on_post_hook_error).await?;
 
         tracing::info!("Successfully uninstalled package {}", name.as_str());
 
@@ -52,20 +64,37 @@ impl PackageUninstaller {
             FileSystemOps::remove_directory(&package_dir)?;
         }
 
+        let hashes: Vec<String> = package.files.iter().map(|f| f.hash.clone()).collect();
+        store::remove_unreferenced(&self.config.storage.install_root, &self.state, &hashes, &package.name)?;
+
         Ok(())
     }
 
-    fn run_pre_uninstall_hooks(&self, package: &InstalledPackage) -> Result<()> {
-        for hook in &self.config.hooks.pre_uninstall {
-            tracing::info!("Running pre-uninstall hook: {}", hook);
-        }
+    async fn run_pre_uninstall_hooks(&self, package: &InstalledPackage, operation_id: Uuid) -> Result<()> {
+        run_hooks(
+            &self.config.hooks.pre_uninstall,
+            HookPhase::PreUninstall,
+            package.name.as_str(),
+            &package.version.to_string(),
+            &PathBuf::from(&package.install_path),
+            operation_id,
+            self.config.hooks.enabled,
+            true,
+        ).await?;
         Ok(())
     }
 
-    fn run_post_uninstall_hooks(&self, package: &InstalledPackage) -> Result<()> {
-        for hook in &self.config.hooks.post_uninstall {
-            tracing::info!("Running post-uninstall hook: {}", hook);
-        }
+    async fn run_post_uninstall_hooks(&self, package: &InstalledPackage, operation_id: Uuid, fatal: bool) -> Result<()> {
+        run_hooks(
+            &self.config.hooks.post_uninstall,
+            HookPhase::PostUninstall,
+            package.name.as_str(),
+            &package.version.to_string(),
+            &PathBuf::from(&package.install_path),
+            operation_id,
+            self.config.hooks.enabled,
+            fatal,
+        ).await?;
         Ok(())
     }
 
@@ -77,6 +106,9 @@ impl PackageUninstaller {
             if install_path.exists() {
                 FileSystemOps::remove_directory(&install_path)?;
             }
+
+            let hashes: Vec<String> = package.files.iter().map(|f| f.hash.clone()).collect();
+            store::remove_unreferenced(&self.config.storage.install_root, &self.state, &hashes, name)?;
         }
 
         self.state.remove_package(name);
@@ -86,11 +118,11 @@ impl PackageUninstaller {
     }
 }
 
-pub fn uninstall_package(
+pub async fn uninstall_package(
     config: Config,
     state: GlobalState,
     name: &PackageName,
 ) -> Result<()> {
     let mut uninstaller = PackageUninstaller::new(config, state);
-    uninstaller.uninstall(name)
+    uninstaller.uninstall(name).await
 }
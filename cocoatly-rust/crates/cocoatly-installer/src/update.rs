@@ -1,11 +1,15 @@
 use cocoatly_core::{
-    types::{PackageName, Version, PackageArtifact, InstalledPackage},
+    types::{PackageName, PackageArtifact, InstalledPackage},
     error::{CocoatlyError, Result},
     config::Config,
-    state::GlobalState,
+    state::{GlobalState, PendingOp},
 };
 use crate::install::{InstallContext, install_package};
-use crate::uninstall::uninstall_package;
+use crate::hooks::{run_hooks, HookPhase};
+use crate::options::InstallOptions;
+use cocoatly_fs::FileSystemOps;
+use std::path::PathBuf;
+use uuid::Uuid;
 
 pub struct PackageUpdater {
     config: Config,
@@ -17,10 +21,16 @@ impl PackageUpdater {
         Self { config, state }
     }
 
+    /// Updates `name` to `new_artifact` atomically: the old install directory
+    /// is moved aside (not deleted) and a journal entry is persisted before
+    /// anything else changes, so a crash or a failed download/verify/extract
+    /// leaves the old version recoverable via `GlobalState::recover` instead
+    /// of gone.
     pub async fn update(
         &mut self,
         name: &PackageName,
         new_artifact: &PackageArtifact,
+        options: InstallOptions,
     ) -> Result<InstalledPackage> {
         tracing::info!(
             "Updating package {} to version {}",
@@ -44,25 +54,88 @@ impl PackageUpdater {
         }
 
         let requested_by = current_package.requested_by.clone();
+        let install_path = PathBuf::from(&current_package.install_path);
+        let backup_path = self.config.storage.temp_dir.join(format!("rollback-{}", Uuid::new_v4()));
 
-        uninstall_package(
-            self.config.clone(),
-            self.state.clone(),
-            name,
-        )?;
+        let pending_op = PendingOp::Update {
+            name: name.clone(),
+            from_version: current_package.version.clone(),
+            to_version: new_artifact.version.clone(),
+            backup_path: backup_path.to_string_lossy().to_string(),
+            install_path: current_package.install_path.clone(),
+            old_package: current_package.clone(),
+        };
+
+        self.state.record_pending_op(&pending_op)?;
+        self.state.save_to_file(&self.config.storage.state_file)?;
+
+        if install_path.exists() {
+            FileSystemOps::ensure_directory(&self.config.storage.temp_dir)?;
+            FileSystemOps::move_directory(&install_path, &backup_path)?;
+        }
+
+        self.state.remove_package(name);
+        self.state.save_to_file(&self.config.storage.state_file)?;
+
+        // The update always carries over the pre-update package's requesters,
+        // regardless of what the caller passed in, so updating a package
+        // never silently erases its dependency tracking.
+        let install_options = options.as_dependency(true);
 
         let context = InstallContext::new(self.config.clone(), self.state.clone())?;
-        let installed = install_package(context, new_artifact, requested_by).await?;
+        let install_result = install_package(context, new_artifact, requested_by, install_options).await;
 
         self.state = GlobalState::load_from_file(&self.config.storage.state_file)?;
 
-        tracing::info!(
-            "Successfully updated package {} to version {}",
-            name.as_str(),
-            new_artifact.version.to_string()
-        );
+        match install_result {
+            Ok(installed) => {
+                if backup_path.exists() {
+                    FileSystemOps::remove_directory(&backup_path)?;
+                }
+                self.state.clear_pending_op(name);
+                self.state.save_to_file(&self.config.storage.state_file)?;
+
+                run_hooks(
+                    &self.config.hooks.post_update,
+                    HookPhase::PostUpdate,
+                    name.as_str(),
+                    &installed.version.to_string(),
+                    &PathBuf::from(&installed.install_path),
+                    Uuid::new_v4(),
+                    self.config.hooks.enabled,
+                    self.config.hooks.fail_on_post_hook_error,
+                ).await?;
+
+                tracing::info!(
+                    "Successfully updated package {} to version {}",
+                    name.as_str(),
+                    new_artifact.version.to_string()
+                );
 
-        Ok(installed)
+                Ok(installed)
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Update of {} to {} failed, rolling back: {}",
+                    name.as_str(),
+                    new_artifact.version.to_string(),
+                    e
+                );
+
+                if backup_path.exists() {
+                    if install_path.exists() {
+                        FileSystemOps::remove_directory(&install_path)?;
+                    }
+                    FileSystemOps::move_directory(&backup_path, &install_path)?;
+                    self.state.add_package(current_package);
+                }
+
+                self.state.clear_pending_op(name);
+                self.state.save_to_file(&self.config.storage.state_file)?;
+
+                Err(e)
+            }
+        }
     }
 }
 
@@ -71,7 +144,8 @@ pub async fn update_package(
     state: GlobalState,
     name: &PackageName,
     new_artifact: &PackageArtifact,
+    options: InstallOptions,
 ) -> Result<InstalledPackage> {
     let mut updater = PackageUpdater::new(config, state);
-    updater.update(name, new_artifact).await
+    updater.update(name, new_artifact, options).await
 }
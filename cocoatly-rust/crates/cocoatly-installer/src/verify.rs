@@ -1,12 +1,16 @@
 use cocoatly_core::{
-    types::{PackageName, InstalledPackage, HashAlgorithm},
+    types::PackageName,
     error::{CocoatlyError, Result},
     config::Config,
     state::GlobalState,
 };
 use cocoatly_crypto::verify_file_hash;
+use cocoatly_compression::extract_archive;
+use cocoatly_downloader::Downloader;
 use cocoatly_fs::FileSystemOps;
+use crate::store;
 use std::path::PathBuf;
+use uuid::Uuid;
 
 pub struct VerificationResult {
     pub package: PackageName,
@@ -30,7 +34,7 @@ pub fn verify_installation(
         return Ok(VerificationResult {
             package: name.clone(),
             valid: false,
-            missing_files: package.files.clone(),
+            missing_files: package.files.iter().map(|f| f.path.clone()).collect(),
             corrupted_files: vec![],
         });
     }
@@ -39,10 +43,15 @@ pub fn verify_installation(
     let mut corrupted_files = Vec::new();
 
     for file in &package.files {
-        let file_path = PathBuf::from(file);
+        let file_path = PathBuf::from(&file.path);
 
         if !file_path.exists() {
-            missing_files.push(file.clone());
+            missing_files.push(file.path.clone());
+            continue;
+        }
+
+        if verify_file_hash(&file_path, &file.hash, &file.algorithm).is_err() {
+            corrupted_files.push(file.path.clone());
         }
     }
 
@@ -56,7 +65,15 @@ pub fn verify_installation(
     })
 }
 
-pub fn repair_package(
+/// Attempts to repair a package flagged invalid by `verify_installation`.
+/// Corrupted files are repaired one at a time by re-linking from their
+/// content-addressed store object when that object's own digest still
+/// checks out (the on-disk copy was merely a bad link or local corruption).
+/// Anything that can't be repaired that way — a missing file, or a corrupted
+/// file whose store object is itself corrupted — is instead repaired by
+/// re-downloading the package's own archive and re-extracting just the
+/// broken entries, rather than demanding a full reinstall.
+pub async fn repair_package(
     config: &Config,
     state: &mut GlobalState,
     name: &PackageName,
@@ -70,17 +87,127 @@ pub fn repair_package(
         return Ok(());
     }
 
-    if !verification.missing_files.is_empty() {
-        return Err(CocoatlyError::VerificationFailed(
-            format!(
-                "Cannot repair package {}: {} files missing",
-                name.as_str(),
-                verification.missing_files.len()
-            )
-        ));
+    let package = state
+        .get_package(name)
+        .ok_or_else(|| CocoatlyError::PackageNotFound(name.as_str().to_string()))?
+        .clone();
+
+    let install_root = &config.storage.install_root;
+
+    let mut needs_redownload = Vec::new();
+
+    for corrupted_path in &verification.corrupted_files {
+        let file = package
+            .files
+            .iter()
+            .find(|f| &f.path == corrupted_path)
+            .ok_or_else(|| CocoatlyError::StateError(
+                format!("Corrupted file {} not found in package record", corrupted_path)
+            ))?;
+
+        let object = store::object_path(install_root, &file.hash);
+        if verify_file_hash(&object, &file.hash, &file.algorithm).is_ok() {
+            let target = PathBuf::from(corrupted_path);
+            std::fs::remove_file(&target)?;
+            store::link_from_store(install_root, &file.hash, &target)?;
+        } else {
+            tracing::warn!(
+                "Store object for {} is itself corrupted; will re-download",
+                corrupted_path
+            );
+            needs_redownload.push(corrupted_path.clone());
+        }
+    }
+
+    needs_redownload.extend(verification.missing_files.iter().cloned());
+
+    if needs_redownload.is_empty() {
+        tracing::info!("Package {} repaired successfully", name.as_str());
+        return Ok(());
     }
 
+    redownload_and_repair(config, &package, install_root, &needs_redownload).await?;
+
     tracing::info!("Package {} repaired successfully", name.as_str());
 
     Ok(())
 }
+
+/// Re-downloads the package's own archive (the same one `cocoatly install`
+/// fetched, re-verified against the recorded checksum), re-extracts it into
+/// a scratch directory, and re-stores + re-links just `broken_paths` from it
+/// instead of requiring a full reinstall.
+async fn redownload_and_repair(
+    config: &Config,
+    package: &cocoatly_core::types::InstalledPackage,
+    install_root: &std::path::Path,
+    broken_paths: &[String],
+) -> Result<()> {
+    tracing::info!(
+        "Re-downloading archive to repair {} file(s) for {}",
+        broken_paths.len(),
+        package.name.as_str()
+    );
+
+    let temp_dir = config.storage.temp_dir.clone();
+    FileSystemOps::ensure_directory(&temp_dir)?;
+
+    let downloader = Downloader::new(config.network.clone())?;
+    let archive_path = temp_dir.join(format!(
+        "{}-{}-repair.tar.gz",
+        package.name.as_str(),
+        package.version.to_string()
+    ));
+
+    downloader.download(&package.download_url, &archive_path, None).await?;
+    verify_file_hash(&archive_path, &package.checksum, &package.checksum_algorithm)?;
+
+    let extract_dir = temp_dir.join(format!("repair-extract-{}", Uuid::new_v4()));
+    FileSystemOps::ensure_directory(&extract_dir)?;
+    extract_archive(&archive_path, &extract_dir)?;
+
+    let install_path = PathBuf::from(&package.install_path);
+
+    let result = (|| -> Result<()> {
+        for broken_path in broken_paths {
+            let target = PathBuf::from(broken_path);
+            let relative = target.strip_prefix(&install_path).map_err(|e| {
+                CocoatlyError::VerificationFailed(
+                    format!("file {} outside install path: {}", broken_path, e)
+                )
+            })?;
+            let source = extract_dir.join(relative);
+
+            let file = package
+                .files
+                .iter()
+                .find(|f| &f.path == broken_path)
+                .ok_or_else(|| CocoatlyError::StateError(
+                    format!("file {} not found in package record", broken_path)
+                ))?;
+
+            verify_file_hash(&source, &file.hash, &file.algorithm).map_err(|_| {
+                CocoatlyError::VerificationFailed(format!(
+                    "re-downloaded archive does not reproduce expected content for {}",
+                    broken_path
+                ))
+            })?;
+
+            if target.exists() {
+                std::fs::remove_file(&target)?;
+            }
+            let object = store::object_path(install_root, &file.hash);
+            if object.exists() && verify_file_hash(&object, &file.hash, &file.algorithm).is_err() {
+                std::fs::remove_file(&object)?;
+            }
+            store::store_file(install_root, &source)?;
+            store::link_from_store(install_root, &file.hash, &target)?;
+        }
+        Ok(())
+    })();
+
+    let _ = std::fs::remove_file(&archive_path);
+    let _ = FileSystemOps::remove_directory(&extract_dir);
+
+    result
+}